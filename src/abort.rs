@@ -0,0 +1,307 @@
+#[cfg(feature = "gaggle")]
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::goose::{GooseMethod, GooseRawRequest, GooseRequest};
+use crate::stats;
+use crate::GooseConfiguration;
+
+/// Exit status of a load test stopped by an abort condition.
+pub const ABORT_EXIT_CODE: i32 = 2;
+
+/// Whether a request failed with a transport error, rather than with a non-2xx status
+/// code or `set_failure`.
+fn is_transport_error(error_kind: &str) -> bool {
+    !error_kind.is_empty() && !error_kind.starts_with("status") && error_kind != "failure"
+}
+
+/// Watches requests for the abort conditions set with `--abort-fail-rate`, `--abort-p95`
+/// and `--abort-transport-errors`. Failure rates and response times are evaluated over
+/// the sliding `--abort-window`, once the load test has run that long.
+pub struct GooseAbortMonitor {
+    /// Abort if more than this percent of requests fail within the window, 0 to disable.
+    fail_rate: usize,
+    /// Abort if the 95th percentile response time within the window exceeds this many
+    /// milliseconds, 0 to disable.
+    p95: usize,
+    /// Abort after this many consecutive transport errors, 0 to disable.
+    transport_errors: usize,
+    /// How many seconds the sliding window covers.
+    window: usize,
+    /// Requests merged by the second of the load test they were received in.
+    buckets: VecDeque<(usize, GooseRequest)>,
+    /// How many transport errors were received since the last other request.
+    consecutive_transport_errors: usize,
+}
+impl GooseAbortMonitor {
+    /// Returns a monitor if any abort condition is configured.
+    pub fn new(configuration: &GooseConfiguration) -> Option<Self> {
+        if configuration.abort_fail_rate == 0
+            && configuration.abort_p95 == 0
+            && configuration.abort_transport_errors == 0
+        {
+            return None;
+        }
+        Some(GooseAbortMonitor {
+            fail_rate: configuration.abort_fail_rate,
+            p95: configuration.abort_p95,
+            transport_errors: configuration.abort_transport_errors,
+            window: configuration.abort_window,
+            buckets: VecDeque::new(),
+            consecutive_transport_errors: 0,
+        })
+    }
+
+    /// Drop requests that are no longer within the window.
+    fn expire(&mut self, elapsed: usize) {
+        while let Some((second, _)) = self.buckets.front() {
+            if second + self.window > elapsed {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Returns the requests received in the current second of the load test.
+    fn bucket(&mut self, elapsed: usize) -> &mut GooseRequest {
+        self.expire(elapsed);
+        if self.buckets.back().map(|(second, _)| *second) != Some(elapsed) {
+            self.buckets
+                .push_back((elapsed, GooseRequest::new("", GooseMethod::GET, 0)));
+        }
+        &mut self.buckets.back_mut().unwrap().1
+    }
+
+    /// Record a request received from a user, `elapsed` seconds into the load test.
+    pub fn record_request(&mut self, raw_request: &GooseRawRequest, elapsed: usize) {
+        let bucket = self.bucket(elapsed);
+        // The request was marked successful or failed with set_success or set_failure.
+        if raw_request.update {
+            if raw_request.success {
+                bucket.success_count += 1;
+                bucket.fail_count = bucket.fail_count.saturating_sub(1);
+            } else {
+                bucket.success_count = bucket.success_count.saturating_sub(1);
+                bucket.fail_count += 1;
+            }
+            return;
+        }
+
+        bucket.set_response_time(raw_request.response_time);
+        if raw_request.success {
+            bucket.success_count += 1;
+        } else {
+            bucket.fail_count += 1;
+        }
+        if is_transport_error(&raw_request.error_kind) {
+            self.consecutive_transport_errors += 1;
+        } else {
+            self.consecutive_transport_errors = 0;
+        }
+    }
+
+    /// Record requests statistics pushed by a worker since its previous push, `elapsed`
+    /// seconds into the load test.
+    #[cfg(feature = "gaggle")]
+    pub fn record_requests(&mut self, requests: &HashMap<String, GooseRequest>, elapsed: usize) {
+        let bucket = self.bucket(elapsed);
+        let mut transport_errors = 0;
+        let mut other_requests = 0;
+        for request in requests.values() {
+            bucket.response_times = stats::merge_response_times(
+                bucket.response_times.clone(),
+                request.response_times.clone(),
+            );
+            bucket.response_time_counter += request.response_time_counter;
+            bucket.min_response_time = stats::update_min_response_time(
+                bucket.min_response_time,
+                request.min_response_time,
+            );
+            bucket.max_response_time = stats::update_max_response_time(
+                bucket.max_response_time,
+                request.max_response_time,
+            );
+            bucket.success_count += request.success_count;
+            bucket.fail_count += request.fail_count;
+
+            let request_transport_errors: usize = request
+                .error_counts
+                .iter()
+                .filter(|(error_kind, _)| is_transport_error(error_kind))
+                .map(|(_, count)| count)
+                .sum();
+            transport_errors += request_transport_errors;
+            other_requests += (request.success_count + request.fail_count)
+                .saturating_sub(request_transport_errors);
+        }
+        // The order of requests within a push is unknown, so transport errors are only
+        // consecutive if nothing else was received.
+        if other_requests > 0 {
+            self.consecutive_transport_errors = 0;
+        } else {
+            self.consecutive_transport_errors += transport_errors;
+        }
+    }
+
+    /// Returns why the load test should abort, `elapsed` seconds into the load test, if
+    /// any abort condition is met.
+    pub fn check(&mut self, elapsed: usize) -> Option<String> {
+        if self.transport_errors > 0 && self.consecutive_transport_errors >= self.transport_errors {
+            return Some(format!(
+                "{} consecutive transport errors, --abort-transport-errors is {}",
+                self.consecutive_transport_errors, self.transport_errors
+            ));
+        }
+
+        // Only evaluate the window once it is full.
+        if elapsed < self.window {
+            return None;
+        }
+        self.expire(elapsed);
+        let mut window = GooseRequest::new("", GooseMethod::GET, 0);
+        for (_, bucket) in &self.buckets {
+            window.response_times =
+                stats::merge_response_times(window.response_times, bucket.response_times.clone());
+            window.response_time_counter += bucket.response_time_counter;
+            window.min_response_time =
+                stats::update_min_response_time(window.min_response_time, bucket.min_response_time);
+            window.max_response_time =
+                stats::update_max_response_time(window.max_response_time, bucket.max_response_time);
+            window.success_count += bucket.success_count;
+            window.fail_count += bucket.fail_count;
+        }
+
+        let total = window.success_count + window.fail_count;
+        if self.fail_rate > 0 && total > 0 {
+            let fail_rate = window.fail_count as f32 / total as f32 * 100.0;
+            if fail_rate > self.fail_rate as f32 {
+                return Some(format!(
+                    "{:.1}% of requests failed in the last {} seconds, --abort-fail-rate is {}%",
+                    fail_rate, self.window, self.fail_rate
+                ));
+            }
+        }
+        if self.p95 > 0 && window.response_time_counter > 0 {
+            let p95 = stats::calculate_response_time_percentile(
+                &window.response_times,
+                window.response_time_counter,
+                window.min_response_time,
+                window.max_response_time,
+                0.95,
+            );
+            if p95 > self.p95 {
+                return Some(format!(
+                    "95th percentile response time was {} ms in the last {} seconds, --abort-p95 is {} ms",
+                    p95, self.window, self.p95
+                ));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_monitor(fail_rate: usize, p95: usize, transport_errors: usize) -> GooseAbortMonitor {
+        let configuration = GooseConfiguration {
+            abort_fail_rate: fail_rate,
+            abort_p95: p95,
+            abort_transport_errors: transport_errors,
+            abort_window: 10,
+            ..Default::default()
+        };
+        GooseAbortMonitor::new(&configuration).unwrap()
+    }
+
+    fn request(response_time: u64, error_kind: &str) -> GooseRawRequest {
+        let mut request = GooseRawRequest::new(GooseMethod::GET, "/", "http://127.0.0.1/", 0, 0);
+        request.response_time = response_time;
+        request.success = error_kind.is_empty();
+        request.error_kind = error_kind.to_string();
+        request
+    }
+
+    #[test]
+    fn disabled() {
+        assert!(GooseAbortMonitor::new(&GooseConfiguration::default()).is_none());
+    }
+
+    #[test]
+    fn fail_rate() {
+        let mut monitor = build_monitor(50, 0, 0);
+        for second in 0..5 {
+            monitor.record_request(&request(10, "status 500"), second);
+            monitor.record_request(&request(10, "status 500"), second);
+            monitor.record_request(&request(10, ""), second);
+        }
+        // The window isn't full yet.
+        assert!(monitor.check(5).is_none());
+        assert!(monitor.check(10).is_some());
+        // Failed requests expire from the window.
+        for second in 10..15 {
+            monitor.record_request(&request(10, ""), second);
+        }
+        assert!(monitor.check(15).is_none());
+
+        // Requests marked as failed with set_failure count as failures.
+        let mut monitor = build_monitor(50, 0, 0);
+        monitor.record_request(&request(10, ""), 1);
+        let mut failure = request(10, "failure");
+        failure.update = true;
+        monitor.record_request(&failure, 1);
+        assert!(monitor.check(10).is_some());
+    }
+
+    #[test]
+    fn p95() {
+        let mut monitor = build_monitor(0, 1000, 0);
+        for _ in 0..19 {
+            monitor.record_request(&request(100, ""), 1);
+        }
+        monitor.record_request(&request(5000, ""), 1);
+        assert!(monitor.check(10).is_none());
+        monitor.record_request(&request(5000, ""), 2);
+        assert!(monitor.check(10).is_some());
+    }
+
+    #[test]
+    fn transport_errors() {
+        let mut monitor = build_monitor(0, 0, 3);
+        monitor.record_request(&request(0, "connect"), 0);
+        monitor.record_request(&request(0, "timeout"), 0);
+        // Any other request resets the count.
+        monitor.record_request(&request(10, "status 503"), 0);
+        monitor.record_request(&request(0, "connect"), 0);
+        monitor.record_request(&request(0, "connect"), 0);
+        assert!(monitor.check(0).is_none());
+        monitor.record_request(&request(0, "connect"), 0);
+        assert!(monitor.check(0).is_some());
+    }
+
+    #[cfg(feature = "gaggle")]
+    #[test]
+    fn worker_transport_errors() {
+        // Workers push merged requests, transport errors are consecutive if nothing
+        // else was received.
+        let mut monitor = build_monitor(0, 0, 3);
+        let mut requests = HashMap::new();
+        let mut failed = GooseRequest::new("/", GooseMethod::GET, 0);
+        failed.fail_count = 2;
+        failed.set_error("connect");
+        failed.set_error("connect");
+        requests.insert("GET /".to_string(), failed.clone());
+        monitor.record_requests(&requests, 0);
+        assert!(monitor.check(0).is_none());
+        monitor.record_requests(&requests, 1);
+        assert!(monitor.check(1).is_some());
+
+        let mut monitor = build_monitor(0, 0, 3);
+        failed.success_count = 1;
+        requests.insert("GET /".to_string(), failed);
+        monitor.record_requests(&requests, 0);
+        monitor.record_requests(&requests, 1);
+        assert!(monitor.check(1).is_none());
+    }
+}
@@ -0,0 +1,426 @@
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use structopt::clap::{App, ArgMatches};
+use structopt::{StructOpt, StructOptInternal};
+
+use crate::GooseConfiguration;
+
+/// Prefix of environment variables setting configuration options, for example
+/// `GOOSE_USERS` sets `--users`.
+const ENV_PREFIX: &str = "GOOSE_";
+
+/// Where the value of a configuration option was loaded from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConfigurationSource {
+    ConfigFile,
+    Environment,
+    CommandLine,
+}
+impl fmt::Display for ConfigurationSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigurationSource::ConfigFile => write!(f, "config file"),
+            ConfigurationSource::Environment => write!(f, "environment"),
+            ConfigurationSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// An option set from the command line, the environment or a configuration file.
+type ResolvedOption = (String, Value, ConfigurationSource);
+
+impl GooseConfiguration {
+    /// Load configuration from the command line, `GOOSE_*` environment variables and
+    /// the TOML file set with `--config-file`, in that order of precedence. Options not
+    /// set anywhere keep their defaults. The options that were set are printed.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::GooseConfiguration;
+    ///
+    ///     let configuration = GooseConfiguration::load().unwrap();
+    /// ```
+    pub fn load() -> Result<GooseConfiguration, String> {
+        GooseConfiguration::load_from_matches(&GooseConfiguration::clap().get_matches())
+            .map(|(configuration, _)| configuration)
+    }
+
+    /// Add Goose's command line options to the command line of a load test with its own
+    /// options, such as `LoadTestOptions::clap()`.
+    pub(crate) fn augment_options<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+        <GooseConfiguration as StructOptInternal>::augment_clap(app)
+    }
+
+    /// Load configuration from parsed command line arguments as `load()` does, also
+    /// returning the name of each option that was set, which defaults set by the load
+    /// test don't override.
+    pub(crate) fn load_from_matches(
+        matches: &ArgMatches,
+    ) -> Result<(GooseConfiguration, HashSet<String>), String> {
+        let configuration = GooseConfiguration::from_clap(matches);
+        let on_command_line = |name: &str| matches.occurrences_of(name.replace('_', "-")) > 0;
+        let env: HashMap<String, String> = std::env::vars()
+            .filter(|(key, _)| key.starts_with(ENV_PREFIX))
+            .collect();
+
+        let config_file = if on_command_line("config_file") {
+            configuration.config_file.clone()
+        } else {
+            env.get("GOOSE_CONFIG_FILE").cloned().unwrap_or_default()
+        };
+        let file = if config_file.is_empty() {
+            None
+        } else {
+            Some(load_config_file(&config_file)?)
+        };
+
+        let (configuration, resolved) = resolve(&configuration, &on_command_line, &env, file)?;
+        // Don't print the configuration before the --list output.
+        if !resolved.is_empty() && !configuration.list {
+            println!("Configuration:");
+            for (name, value, source) in &resolved {
+                println!("  {} = {} ({})", name, value, source);
+            }
+            println!();
+        }
+        let configured = resolved.into_iter().map(|(name, _, _)| name).collect();
+        Ok((configuration, configured))
+    }
+}
+
+/// Read a TOML configuration file into a table of options, keyed by option name with
+/// dashes replaced by underscores.
+fn load_config_file(path: &str) -> Result<Map<String, Value>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("failed to read {}: {}", path, e)),
+    };
+    parse_config_file(&contents).map_err(|e| format!("failed to parse {}: {}", path, e))
+}
+
+fn parse_config_file(contents: &str) -> Result<Map<String, Value>, String> {
+    let table: toml::value::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+    let mut options = Map::new();
+    for (key, value) in table {
+        let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        options.insert(key.replace('-', "_"), value);
+    }
+    Ok(options)
+}
+
+/// Layer options from a configuration file, the environment and the command line over
+/// the defaults. Returns the resolved configuration and each option that was set,
+/// sorted by name.
+fn resolve(
+    configuration: &GooseConfiguration,
+    on_command_line: &dyn Fn(&str) -> bool,
+    env: &HashMap<String, String>,
+    file: Option<Map<String, Value>>,
+) -> Result<(GooseConfiguration, Vec<ResolvedOption>), String> {
+    let mut options = match serde_json::to_value(configuration) {
+        Ok(Value::Object(o)) => o,
+        _ => return Err("failed to serialize configuration".to_string()),
+    };
+    let file = file.unwrap_or_default();
+    for key in file.keys() {
+        if !options.contains_key(key) || key == "config_file" {
+            return Err(format!("unknown option {} in config file", key));
+        }
+    }
+
+    let mut resolved = Vec::new();
+    let names: Vec<String> = options.keys().cloned().collect();
+    for name in names {
+        if on_command_line(&name) {
+            resolved.push((
+                name.to_string(),
+                options[&name].clone(),
+                ConfigurationSource::CommandLine,
+            ));
+            continue;
+        }
+
+        let env_name = format!("{}{}", ENV_PREFIX, name.to_uppercase());
+        let (value, origin) = if let Some(raw) = env.get(&env_name) {
+            let value = from_env(&options[&name], raw)
+                .map_err(|e| format!("invalid value for {}: {}", env_name, e))?;
+            (value, ConfigurationSource::Environment)
+        } else if let Some(value) = file.get(&name) {
+            (
+                coerce(&options[&name], value.clone()),
+                ConfigurationSource::ConfigFile,
+            )
+        } else {
+            continue;
+        };
+
+        // Validate each option as it is set, to report which one is invalid.
+        options.insert(name.to_string(), value.clone());
+        if let Err(e) = serde_json::from_value::<GooseConfiguration>(Value::Object(options.clone()))
+        {
+            let option = match origin {
+                ConfigurationSource::Environment => env_name,
+                _ => format!("{} in config file", name),
+            };
+            return Err(format!("invalid value for {}: {}", option, e));
+        }
+        resolved.push((name, value, origin));
+    }
+
+    match serde_json::from_value(Value::Object(options)) {
+        Ok(c) => Ok((c, resolved)),
+        Err(e) => Err(format!("invalid configuration: {}", e)),
+    }
+}
+
+/// Convert an environment variable to the type of the option's default value. Options
+/// that can be repeated take a single value, or a JSON array of values.
+fn from_env(default: &Value, raw: &str) -> Result<Value, String> {
+    match default {
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Value::Bool(true)),
+            "0" | "false" | "no" | "off" | "" => Ok(Value::Bool(false)),
+            _ => Err(format!("expected true or false, found {}", raw)),
+        },
+        // Options without a default are numbers, or names such as the HTTP version.
+        Value::Null if raw.is_empty() => Ok(Value::Null),
+        Value::Null => match raw.parse::<u64>() {
+            Ok(n) => Ok(Value::from(n)),
+            Err(_) => Ok(Value::String(raw.to_string())),
+        },
+        Value::Number(_) => match raw.parse::<u64>() {
+            Ok(n) => Ok(Value::from(n)),
+            Err(_) => Err(format!("expected a number, found {}", raw)),
+        },
+        Value::Array(_) => {
+            if raw.trim_start().starts_with('[') {
+                serde_json::from_str(raw).map_err(|e| e.to_string())
+            } else {
+                Ok(Value::Array(vec![Value::String(raw.to_string())]))
+            }
+        }
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Convert a configuration file value or a default set by the load test to the type of
+/// the option's default value where this is unambiguous, such as `run_time = 300` or
+/// `host = "http://localhost"`.
+fn coerce(default: &Value, value: Value) -> Value {
+    match (default, value) {
+        (Value::String(_), Value::Number(n)) => Value::String(n.to_string()),
+        (Value::String(_), Value::Bool(b)) => Value::String(b.to_string()),
+        (Value::Array(_), Value::String(s)) => Value::Array(vec![Value::String(s)]),
+        (_, value) => value,
+    }
+}
+
+/// Returns the name of each option of an already loaded configuration that differs
+/// from the built-in defaults.
+pub(crate) fn changed_options(configuration: &GooseConfiguration) -> HashSet<String> {
+    let defaults = serde_json::to_value(GooseConfiguration::from_iter(vec!["goose"]));
+    let options = serde_json::to_value(configuration);
+    match (defaults, options) {
+        (Ok(Value::Object(defaults)), Ok(Value::Object(options))) => options
+            .into_iter()
+            .filter(|(name, value)| defaults.get(name) != Some(value))
+            .map(|(name, _)| name)
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
+/// Apply a default set by the load test to an option, converting it to the type of the
+/// option and confirming it is valid. Defaults for options that can be repeated are
+/// combined with earlier defaults when `combine` is set, otherwise the default replaces
+/// the option's value.
+pub(crate) fn apply_default(
+    configuration: &GooseConfiguration,
+    name: &str,
+    value: Value,
+    combine: bool,
+) -> Result<GooseConfiguration, String> {
+    let mut options = match serde_json::to_value(configuration) {
+        Ok(Value::Object(o)) => o,
+        _ => return Err("failed to serialize configuration".to_string()),
+    };
+    let current = match options.get_mut(name) {
+        Some(c) => c,
+        None => return Err(format!("unknown option {}", name)),
+    };
+    let value = coerce(current, value);
+    match (current, value) {
+        (Value::Array(current), Value::Array(values)) if combine => current.extend(values),
+        (current, value) => *current = value,
+    }
+    serde_json::from_value(Value::Object(options)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> GooseConfiguration {
+        GooseConfiguration::from_iter(vec!["goose"])
+    }
+
+    #[test]
+    fn precedence() {
+        let file = parse_config_file(
+            "users = 5\n\
+             hatch-rate = 2\n\
+             run_time = 300\n\
+             host = \"http://file\"\n\
+             manager_bind_host = \"10.0.0.1\"\n\
+             http_version = 1.1\n",
+        )
+        .unwrap();
+        let mut env = HashMap::new();
+        env.insert("GOOSE_USERS".to_string(), "10".to_string());
+        env.insert("GOOSE_HATCH_RATE".to_string(), "3".to_string());
+        env.insert("GOOSE_NO_STATS".to_string(), "true".to_string());
+
+        let configuration = GooseConfiguration::from_iter(vec!["goose", "--hatch-rate", "4"]);
+        let on_command_line = |name: &str| name == "hatch_rate";
+        let (configuration, resolved) =
+            resolve(&configuration, &on_command_line, &env, Some(file)).unwrap();
+
+        // Command line over environment over config file over defaults.
+        assert_eq!(configuration.hatch_rate, 4);
+        assert_eq!(configuration.users, Some(10));
+        assert!(configuration.no_stats);
+        assert_eq!(configuration.run_time, "300");
+        assert_eq!(configuration.host, "http://file");
+        assert_eq!(configuration.manager_bind_host, "10.0.0.1");
+        assert_eq!(
+            configuration.http_version,
+            Some(crate::goose::GooseHttpVersion::Http1)
+        );
+        assert_eq!(configuration.log_file, "goose.log");
+
+        let sources: Vec<(&str, ConfigurationSource)> = resolved
+            .iter()
+            .map(|(name, _, source)| (name.as_str(), *source))
+            .collect();
+        assert!(sources.contains(&("hatch_rate", ConfigurationSource::CommandLine)));
+        assert!(sources.contains(&("users", ConfigurationSource::Environment)));
+        assert!(sources.contains(&("run_time", ConfigurationSource::ConfigFile)));
+        assert!(!sources.iter().any(|(name, _)| *name == "log_file"));
+    }
+
+    #[test]
+    fn environment_values() {
+        let mut env = HashMap::new();
+        env.insert("GOOSE_HOST".to_string(), "http://a".to_string());
+        env.insert(
+            "GOOSE_NAMED_HOST".to_string(),
+            "[\"api=http://b\", \"cdn=http://c\"]".to_string(),
+        );
+        env.insert("GOOSE_HEADER".to_string(), "Accept: a, b".to_string());
+        env.insert("GOOSE_TIMEOUT".to_string(), "30".to_string());
+        env.insert("GOOSE_GZIP".to_string(), "0".to_string());
+        env.insert("GOOSE_CONNECTION_POOL".to_string(), "shared".to_string());
+        env.insert("GOOSE_HTTP_VERSION".to_string(), "2".to_string());
+        let (configuration, _) = resolve(&defaults(), &|_| false, &env, None).unwrap();
+        assert_eq!(configuration.host, "http://a");
+        assert_eq!(
+            configuration.named_host,
+            vec!["api=http://b", "cdn=http://c"]
+        );
+        assert_eq!(configuration.header, vec!["Accept: a, b"]);
+        assert_eq!(configuration.timeout, Some(30));
+        assert!(!configuration.gzip);
+        assert_eq!(
+            configuration.connection_pool,
+            crate::goose::GooseConnectionPool::Shared
+        );
+        assert_eq!(
+            configuration.http_version,
+            Some(crate::goose::GooseHttpVersion::Http2)
+        );
+
+        let mut env = HashMap::new();
+        env.insert("GOOSE_USERS".to_string(), "many".to_string());
+        assert!(resolve(&defaults(), &|_| false, &env, None).is_err());
+
+        let mut env = HashMap::new();
+        env.insert("GOOSE_NO_STATS".to_string(), "maybe".to_string());
+        assert!(resolve(&defaults(), &|_| false, &env, None).is_err());
+
+        let mut env = HashMap::new();
+        env.insert("GOOSE_CONNECTION_POOL".to_string(), "global".to_string());
+        assert!(resolve(&defaults(), &|_| false, &env, None).is_err());
+
+        let mut env = HashMap::new();
+        env.insert("GOOSE_HTTP_VERSION".to_string(), "3".to_string());
+        assert!(resolve(&defaults(), &|_| false, &env, None).is_err());
+    }
+
+    #[test]
+    fn invalid_config_file() {
+        let file = parse_config_file("not_an_option = 1\n").unwrap();
+        assert!(resolve(&defaults(), &|_| false, &HashMap::new(), Some(file)).is_err());
+
+        let file = parse_config_file("users = \"many\"\n").unwrap();
+        assert!(resolve(&defaults(), &|_| false, &HashMap::new(), Some(file)).is_err());
+
+        assert!(parse_config_file("users = ").is_err());
+    }
+
+    #[test]
+    fn load_test_defaults() {
+        let configuration = GooseConfiguration::from_iter(vec!["goose"]);
+        let configuration = apply_default(&configuration, "users", Value::from(50), false).unwrap();
+        assert_eq!(configuration.users, Some(50));
+        let configuration =
+            apply_default(&configuration, "run_time", Value::from(600), false).unwrap();
+        assert_eq!(configuration.run_time, "600");
+        let configuration =
+            apply_default(&configuration, "host", Value::from("http://a"), false).unwrap();
+        assert_eq!(configuration.host, "http://a");
+        let configuration = apply_default(
+            &configuration,
+            "stats_log_format",
+            Value::from("csv"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(configuration.stats_log_format, "csv");
+
+        // Defaults for options that can be repeated are combined.
+        let configuration = apply_default(
+            &configuration,
+            "named_host",
+            Value::from("api=http://b"),
+            false,
+        )
+        .unwrap();
+        let configuration = apply_default(
+            &configuration,
+            "named_host",
+            Value::from("cdn=http://c"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            configuration.named_host,
+            vec!["api=http://b", "cdn=http://c"]
+        );
+
+        assert!(apply_default(&configuration, "users", Value::from("many"), false).is_err());
+        assert!(apply_default(&configuration, "no_stats", Value::from(3), false).is_err());
+        assert!(apply_default(&configuration, "unknown", Value::from(3), false).is_err());
+    }
+
+    #[test]
+    fn changed() {
+        let configuration =
+            GooseConfiguration::from_iter(vec!["goose", "--users", "3", "--no-stats"]);
+        let changed = changed_options(&configuration);
+        assert_eq!(changed.len(), 2);
+        assert!(changed.contains("users"));
+        assert!(changed.contains("no_stats"));
+    }
+}
@@ -0,0 +1,223 @@
+use cookie::Cookie;
+use cookie_store::CookieStore;
+use serde::Deserialize;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::goose::GooseCookie;
+
+/// A cookie as exported from a browser in JSON format.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCookie {
+    domain: String,
+    name: String,
+    value: String,
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    #[serde(default)]
+    host_only: bool,
+    #[serde(default)]
+    expiration_date: Option<f64>,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Load cookies from a file, returning each cookie with the URL it is stored for.
+/// Files starting with `[` are parsed as a JSON array of cookies as exported by
+/// browser extensions, all other files are parsed as Netscape `cookies.txt` files.
+/// Expired cookies are ignored.
+pub fn load_cookies_file(path: &str) -> Result<Vec<(Url, GooseCookie)>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("failed to read {}: {}", path, e)),
+    };
+    if contents.trim_start().starts_with('[') {
+        parse_json(&contents)
+    } else {
+        parse_netscape(&contents)
+    }
+}
+
+/// Build a cookie jar holding the cookies loaded from a file.
+pub(crate) fn build_cookie_jar(cookies: &[(Url, GooseCookie)]) -> CookieStore {
+    let mut jar = CookieStore::default();
+    for (url, cookie) in cookies {
+        if let Err(e) = jar.insert_raw(cookie, url) {
+            warn!("failed to store cookie {}: {}", cookie.name(), e);
+        }
+    }
+    jar
+}
+
+/// Parse a JSON array of cookies.
+fn parse_json(contents: &str) -> Result<Vec<(Url, GooseCookie)>, String> {
+    let json_cookies: Vec<JsonCookie> = match serde_json::from_str(contents) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("invalid json cookies: {}", e)),
+    };
+    let mut cookies = Vec::new();
+    for c in json_cookies {
+        if let Some(expires) = c.expiration_date {
+            if is_expired(expires as u64) {
+                continue;
+            }
+        }
+        cookies.push(build_cookie(
+            &c.domain,
+            c.host_only,
+            &c.path,
+            c.secure,
+            c.http_only,
+            &c.name,
+            &c.value,
+        )?);
+    }
+    Ok(cookies)
+}
+
+/// Parse a Netscape `cookies.txt` file, with one tab separated cookie per line:
+/// domain, include subdomains, path, secure, expires, name and value.
+fn parse_netscape(contents: &str) -> Result<Vec<(Url, GooseCookie)>, String> {
+    let mut cookies = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        // HttpOnly cookies are prefixed, all other lines starting with # are comments.
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(l) => (l, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return Err(format!(
+                "invalid netscape cookie on line {}: expected 7 fields, found {}",
+                number + 1,
+                fields.len()
+            ));
+        }
+        let expires = match fields[4].parse::<u64>() {
+            Ok(e) => e,
+            Err(e) => {
+                return Err(format!(
+                    "invalid netscape cookie expiration on line {}: {}",
+                    number + 1,
+                    e
+                ))
+            }
+        };
+        // An expiration of 0 is a session cookie.
+        if expires > 0 && is_expired(expires) {
+            continue;
+        }
+        cookies.push(build_cookie(
+            fields[0],
+            fields[1] != "TRUE",
+            fields[2],
+            fields[3] == "TRUE",
+            http_only,
+            fields[5],
+            fields[6],
+        )?);
+    }
+    Ok(cookies)
+}
+
+fn is_expired(expires: u64) -> bool {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(now) => expires < now.as_secs(),
+        Err(_) => false,
+    }
+}
+
+/// Build a cookie, and the URL it would have been set from.
+fn build_cookie(
+    domain: &str,
+    host_only: bool,
+    path: &str,
+    secure: bool,
+    http_only: bool,
+    name: &str,
+    value: &str,
+) -> Result<(Url, GooseCookie), String> {
+    let host = domain.trim_start_matches('.');
+    let mut cookie = Cookie::build(name.to_string(), value.to_string())
+        .path(path.to_string())
+        .secure(secure)
+        .http_only(http_only)
+        .finish();
+    // Cookies without a domain are only sent to the host that set them.
+    if !host_only {
+        cookie.set_domain(host.to_string());
+    }
+    let scheme = if secure { "https" } else { "http" };
+    match Url::parse(&format!("{}://{}{}", scheme, host, path)) {
+        Ok(url) => Ok((url, cookie)),
+        Err(e) => Err(format!("invalid cookie domain {}: {}", domain, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn netscape() {
+        let contents = "# Netscape HTTP Cookie File\n\
+                        \n\
+                        .example.com\tTRUE\t/\tFALSE\t0\tsession\tabc\n\
+                        #HttpOnly_www.example.com\tFALSE\t/admin\tTRUE\t0\ttoken\t123\n\
+                        .example.com\tTRUE\t/\tFALSE\t1\texpired\tgone\n";
+        let cookies = parse_netscape(contents).unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        let (url, cookie) = &cookies[0];
+        assert_eq!(url.as_str(), "http://example.com/");
+        assert_eq!(cookie.name(), "session");
+        assert_eq!(cookie.value(), "abc");
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.http_only(), Some(false));
+
+        let (url, cookie) = &cookies[1];
+        assert_eq!(url.as_str(), "https://www.example.com/admin");
+        assert_eq!(cookie.name(), "token");
+        assert_eq!(cookie.domain(), None);
+        assert_eq!(cookie.path(), Some("/admin"));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+
+        assert!(parse_netscape("example.com\tTRUE\t/\n").is_err());
+    }
+
+    #[test]
+    fn json() {
+        let contents = r#"[
+            {"domain": ".example.com", "name": "session", "value": "abc", "path": "/"},
+            {"domain": "www.example.com", "name": "token", "value": "123", "hostOnly": true, "httpOnly": true},
+            {"domain": ".example.com", "name": "expired", "value": "gone", "expirationDate": 1.5}
+        ]"#;
+        let cookies = parse_json(contents).unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        let (url, cookie) = &cookies[0];
+        assert_eq!(url.as_str(), "http://example.com/");
+        assert_eq!(cookie.name(), "session");
+        assert_eq!(cookie.domain(), Some("example.com"));
+
+        let (url, cookie) = &cookies[1];
+        assert_eq!(url.as_str(), "http://www.example.com/");
+        assert_eq!(cookie.value(), "123");
+        assert_eq!(cookie.domain(), None);
+        assert_eq!(cookie.http_only(), Some(true));
+
+        assert!(parse_json("[{\"name\": \"missing domain\"}]").is_err());
+    }
+}
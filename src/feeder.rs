@@ -0,0 +1,376 @@
+//! Feeders hand records of test data, such as credentials or search terms, to users.
+//!
+//! Records are loaded from CSV files with a header row, or from JSON lines files with
+//! one JSON object per line. Each record maps column names (or object keys) to values.
+//!
+//! # Example
+//! ```rust,no_run
+//!     use goose::prelude::*;
+//!     use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+//!
+//!     GooseAttack::initialize()
+//!         .register_feeder(
+//!             GooseFeeder::csv("users", "users.csv").set_strategy(GooseFeederStrategy::Unique),
+//!         )
+//!         .register_taskset(taskset!("LoadtestTasks")
+//!             .register_task(task!(login).set_on_start())
+//!         )
+//!         .execute();
+//!
+//!     async fn login(user: &GooseUser) {
+//!         if let Some(record) = user.feed("users") {
+//!             let params = [("name", &record["username"]), ("pass", &record["password"])];
+//!             let request_builder = user.goose_post("/login").await;
+//!             let _response = user.goose_send(request_builder.form(&params), None).await;
+//!         }
+//!     }
+//! ```
+
+use rand::Rng;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// A single record of test data, mapping column names to values.
+pub type GooseRecord = HashMap<String, String>;
+
+/// How a feeder hands records to users.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GooseFeederStrategy {
+    /// Each user reads all records in order, starting over after the last record.
+    Sequential,
+    /// Each request for a record returns a random record.
+    Random,
+    /// All users share one pass through the records in order, starting over after
+    /// the last record.
+    Circular,
+    /// Each record is handed out one time, to one user. No records are returned once
+    /// all records have been handed out. In Gaggle mode, records are partitioned across
+    /// workers so they stay unique.
+    Unique,
+}
+
+/// The format of a feeder's file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GooseFeederFormat {
+    Csv,
+    JsonLines,
+}
+
+/// A named source of test data records, registered with `GooseAttack::register_feeder`.
+#[derive(Debug)]
+pub struct GooseFeeder {
+    /// The name used to request records with `GooseUser::feed`.
+    pub name: String,
+    /// The file records are loaded from.
+    pub path: String,
+    /// How records are handed to users.
+    pub strategy: GooseFeederStrategy,
+    format: GooseFeederFormat,
+    records: Vec<GooseRecord>,
+    position: AtomicUsize,
+    exhausted: AtomicBool,
+}
+impl Clone for GooseFeeder {
+    fn clone(&self) -> Self {
+        GooseFeeder {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            strategy: self.strategy,
+            format: self.format,
+            records: self.records.clone(),
+            position: AtomicUsize::new(self.position.load(Ordering::SeqCst)),
+            exhausted: AtomicBool::new(self.exhausted.load(Ordering::SeqCst)),
+        }
+    }
+}
+impl GooseFeeder {
+    fn new(name: &str, path: &str, format: GooseFeederFormat) -> Self {
+        trace!("new feeder: {} ({})", name, path);
+        GooseFeeder {
+            name: name.to_string(),
+            path: path.to_string(),
+            strategy: GooseFeederStrategy::Sequential,
+            format,
+            records: Vec::new(),
+            position: AtomicUsize::new(0),
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a feeder loading records from a CSV file. The first row names the columns.
+    /// Fields can be quoted, and quoted fields can contain commas, quotes and line breaks.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::feeder::GooseFeeder;
+    ///
+    ///     let feeder = GooseFeeder::csv("users", "users.csv");
+    /// ```
+    pub fn csv(name: &str, path: &str) -> Self {
+        GooseFeeder::new(name, path, GooseFeederFormat::Csv)
+    }
+
+    /// Create a feeder loading records from a JSON lines file, with one JSON object per
+    /// line. Values that aren't strings are converted to their JSON representation.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::feeder::GooseFeeder;
+    ///
+    ///     let feeder = GooseFeeder::json_lines("products", "products.jsonl");
+    /// ```
+    pub fn json_lines(name: &str, path: &str) -> Self {
+        GooseFeeder::new(name, path, GooseFeederFormat::JsonLines)
+    }
+
+    /// Set how records are handed to users, defaults to `GooseFeederStrategy::Sequential`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+    ///
+    ///     let feeder = GooseFeeder::csv("terms", "terms.csv").set_strategy(GooseFeederStrategy::Random);
+    /// ```
+    pub fn set_strategy(mut self, strategy: GooseFeederStrategy) -> Self {
+        trace!("{} set_strategy: {:?}", self.name, strategy);
+        self.strategy = strategy;
+        self
+    }
+
+    /// Load all records from the feeder's file.
+    pub(crate) fn load(&mut self) -> Result<(), String> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("failed to read {}: {}", self.path, e)),
+        };
+        self.records = match self.format {
+            GooseFeederFormat::Csv => parse_csv(&contents)?,
+            GooseFeederFormat::JsonLines => parse_json_lines(&contents)?,
+        };
+        info!(
+            "loaded {} records for feeder {} from {}",
+            self.records.len(),
+            self.name,
+            self.path
+        );
+        Ok(())
+    }
+
+    /// Copy the feeder for a Gaggle worker. Unique records are partitioned across
+    /// workers, so each worker only hands out its own share of records. Workers are
+    /// numbered from 1.
+    #[cfg(feature = "gaggle")]
+    pub(crate) fn partition(&self, worker_id: usize, workers: usize) -> GooseFeeder {
+        let mut feeder = GooseFeeder::new(&self.name, &self.path, self.format);
+        feeder.strategy = self.strategy;
+        feeder.records = if self.strategy == GooseFeederStrategy::Unique && workers > 1 {
+            self.records
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| index % workers == (worker_id - 1) % workers)
+                .map(|(_, record)| record.clone())
+                .collect()
+        } else {
+            self.records.clone()
+        };
+        feeder
+    }
+
+    /// The number of records loaded.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether no records are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Get the next record. `user_position` tracks the position of the requesting user,
+    /// for the `Sequential` strategy, and `rng` is the requesting user's random number
+    /// generator, for the `Random` strategy.
+    pub(crate) fn next_record<R: Rng>(
+        &self,
+        user_position: &mut usize,
+        rng: &mut R,
+    ) -> Option<GooseRecord> {
+        if self.records.is_empty() {
+            return None;
+        }
+        let index = match self.strategy {
+            GooseFeederStrategy::Sequential => {
+                let index = *user_position % self.records.len();
+                *user_position += 1;
+                index
+            }
+            GooseFeederStrategy::Random => rng.gen_range(0, self.records.len()),
+            GooseFeederStrategy::Circular => {
+                self.position.fetch_add(1, Ordering::SeqCst) % self.records.len()
+            }
+            GooseFeederStrategy::Unique => {
+                let index = self.position.fetch_add(1, Ordering::SeqCst);
+                if index >= self.records.len() {
+                    // Only warn the first time.
+                    if !self.exhausted.swap(true, Ordering::SeqCst) {
+                        warn!(
+                            "feeder {} is exhausted, all {} records have been used",
+                            self.name,
+                            self.records.len()
+                        );
+                    }
+                    return None;
+                }
+                index
+            }
+        };
+        Some(self.records[index].clone())
+    }
+}
+
+/// Parse CSV records, using the first line as column names.
+pub(crate) fn parse_csv(contents: &str) -> Result<Vec<GooseRecord>, String> {
+    csv::Reader::from_reader(contents.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<GooseRecord>, csv::Error>>()
+        .map_err(|e| format!("invalid csv record: {}", e))
+}
+
+/// Parse JSON lines records, with one JSON object per line.
+fn parse_json_lines(contents: &str) -> Result<Vec<GooseRecord>, String> {
+    let mut records = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = match serde_json::from_str(line) {
+            Ok(o) => o,
+            Err(e) => return Err(format!("invalid json record on line {}: {}", number + 1, e)),
+        };
+        let record = object
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => (key, s),
+                other => (key, other.to_string()),
+            })
+            .collect();
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeder(strategy: GooseFeederStrategy, count: usize) -> GooseFeeder {
+        let mut feeder = GooseFeeder::csv("test", "test.csv").set_strategy(strategy);
+        for index in 0..count {
+            let mut record = GooseRecord::new();
+            record.insert("index".to_string(), index.to_string());
+            feeder.records.push(record);
+        }
+        feeder
+    }
+
+    fn indexes(feeder: &GooseFeeder, user_position: &mut usize, count: usize) -> Vec<String> {
+        (0..count)
+            .map(
+                |_| match feeder.next_record(user_position, &mut rand::thread_rng()) {
+                    Some(record) => record["index"].clone(),
+                    None => "none".to_string(),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn csv() {
+        let contents = "username,password,note\n\
+                        alice,secret,\"hello, world\"\n\
+                        \n\
+                        bob,\"pass\"\"word\",\n";
+        let records = parse_csv(contents).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["username"], "alice");
+        assert_eq!(records[0]["note"], "hello, world");
+        assert_eq!(records[1]["password"], "pass\"word");
+        assert_eq!(records[1]["note"], "");
+
+        // Quoted fields can span lines.
+        let records = parse_csv("name,address\nalice,\"1 Main St\nSpringfield\"\n").unwrap();
+        assert_eq!(records[0]["address"], "1 Main St\nSpringfield");
+
+        assert!(parse_csv("a,b\n1,2,3\n").is_err());
+        assert_eq!(parse_csv("").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn json_lines() {
+        let contents = "{\"sku\": \"A-1\", \"price\": 10, \"tags\": [\"a\"]}\n\
+                        \n\
+                        {\"sku\": \"B-2\"}\n";
+        let records = parse_json_lines(contents).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["sku"], "A-1");
+        assert_eq!(records[0]["price"], "10");
+        assert_eq!(records[0]["tags"], "[\"a\"]");
+        assert_eq!(records[1]["sku"], "B-2");
+
+        assert!(parse_json_lines("{\"sku\": \"A-1\"}\n[1, 2]\n").is_err());
+    }
+
+    #[test]
+    fn strategies() {
+        // Each user reads all records in order.
+        let sequential = feeder(GooseFeederStrategy::Sequential, 3);
+        let (mut first_user, mut second_user) = (0, 0);
+        assert_eq!(
+            indexes(&sequential, &mut first_user, 4),
+            ["0", "1", "2", "0"]
+        );
+        assert_eq!(indexes(&sequential, &mut second_user, 2), ["0", "1"]);
+
+        // All users share one pass through the records.
+        let circular = feeder(GooseFeederStrategy::Circular, 3);
+        assert_eq!(indexes(&circular, &mut first_user, 2), ["0", "1"]);
+        assert_eq!(indexes(&circular, &mut second_user, 2), ["2", "0"]);
+
+        // Records are only handed out once.
+        let unique = feeder(GooseFeederStrategy::Unique, 3);
+        assert_eq!(indexes(&unique, &mut first_user, 2), ["0", "1"]);
+        assert_eq!(indexes(&unique, &mut second_user, 2), ["2", "none"]);
+
+        // Random records are always valid records.
+        let random = feeder(GooseFeederStrategy::Random, 3);
+        for index in indexes(&random, &mut first_user, 10) {
+            assert!(["0", "1", "2"].contains(&index.as_str()));
+        }
+
+        // Empty feeders have no records.
+        let empty = feeder(GooseFeederStrategy::Circular, 0);
+        assert_eq!(indexes(&empty, &mut first_user, 1), ["none"]);
+    }
+
+    #[test]
+    #[cfg(feature = "gaggle")]
+    fn partition() {
+        // Unique records are partitioned across workers.
+        let unique = feeder(GooseFeederStrategy::Unique, 5);
+        let (mut first_user, mut second_user) = (0, 0);
+        let first_worker = unique.partition(1, 2);
+        let second_worker = unique.partition(2, 2);
+        assert_eq!(
+            indexes(&first_worker, &mut first_user, 4),
+            ["0", "2", "4", "none"]
+        );
+        assert_eq!(
+            indexes(&second_worker, &mut second_user, 3),
+            ["1", "3", "none"]
+        );
+
+        // Other strategies share all records.
+        let circular = feeder(GooseFeederStrategy::Circular, 5);
+        assert_eq!(circular.partition(2, 2).len(), 5);
+    }
+}
@@ -252,23 +252,49 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 
+use cookie::Cookie;
+use cookie_store::CookieStore;
+use flate2::read::{GzDecoder, ZlibDecoder};
 use http::method::Method;
-use http::StatusCode;
-use reqwest::{header, Client, ClientBuilder, Error, RequestBuilder, Response};
+use http::{StatusCode, Version};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use regex::Regex;
+use reqwest::{
+    header, redirect, Client, ClientBuilder, Error, Request, RequestBuilder, Response,
+    ResponseBuilderExt,
+};
 use serde::{Deserialize, Serialize};
+use std::any::Any;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::net::IpAddr;
+use std::str::FromStr;
 use std::sync::atomic::AtomicUsize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+use std::time::Duration;
 use std::{future::Future, pin::Pin, time::Instant};
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{mpsc, RwLock};
 use url::Url;
 
+use crate::cookies;
+use crate::feeder::{GooseFeeder, GooseRecord};
+use crate::har::GooseHarPages;
+use crate::replay::GooseReplay;
 use crate::GooseConfiguration;
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
+/// How many redirects Goose follows before returning the redirect response, unless
+/// `--max-redirects` is set.
+const MAX_REDIRECTS: usize = 10;
+
+/// A cookie stored in a GooseUser's cookie jar.
+pub type GooseCookie = Cookie<'static>;
+
 /// task!(foo) expands to GooseTask::new(foo), but also does some boxing to work around a limitation in the compiler.
 #[macro_export]
 macro_rules! task {
@@ -308,6 +334,22 @@ pub struct GooseTaskSet {
     pub weighted_on_stop_tasks: Vec<Vec<usize>>,
     /// An optional default host to run this TaskSet against.
     pub host: Option<String>,
+    /// An optional HTTP version used by users running this TaskSet.
+    pub http_version: Option<GooseHttpVersion>,
+    /// An optional authentication provider used by users running this TaskSet.
+    pub auth: Option<GooseAuth>,
+    /// An optional proxy used by users running this TaskSet.
+    pub proxy: Option<String>,
+    /// Tags selecting this TaskSet with `--tags` and `--exclude-tags`, inherited by its tasks.
+    pub tags: Vec<String>,
+    /// An optional fixed number of users running this TaskSet, instead of a weighted share.
+    pub fixed_users: Option<usize>,
+    /// An optional minimum number of users running this TaskSet, in addition to a weighted share.
+    pub min_users: Option<usize>,
+    /// An optional number of times each user runs this TaskSet before stopping.
+    pub iterations: Option<usize>,
+    /// The requests of each page, for TaskSets built from a HAR file with `GooseHar`.
+    pub(crate) har: Option<Arc<GooseHarPages>>,
 }
 impl GooseTaskSet {
     /// Creates a new GooseTaskSet. Once created, GooseTasks must be assigned to it, and finally it must be
@@ -332,6 +374,14 @@ impl GooseTaskSet {
             weighted_on_start_tasks: Vec::new(),
             weighted_on_stop_tasks: Vec::new(),
             host: None,
+            http_version: None,
+            auth: None,
+            proxy: None,
+            tags: Vec::new(),
+            fixed_users: None,
+            min_users: None,
+            iterations: None,
+            har: None,
         }
     }
 
@@ -397,6 +447,133 @@ impl GooseTaskSet {
         self
     }
 
+    /// Set the HTTP version used by users running this task set. If no `--http-version`
+    /// flag is set when running the load test, clients will be built with this version.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use goose::goose::GooseHttpVersion;
+    ///
+    ///     let mut example_tasks = taskset!("ExampleTasks").set_http_version(GooseHttpVersion::Http2);
+    /// ```
+    pub fn set_http_version(mut self, http_version: GooseHttpVersion) -> Self {
+        trace!("{} set_http_version: {}", self.name, http_version);
+        self.http_version = Some(http_version);
+        self
+    }
+
+    /// Set an authentication provider for the task set. Each user running this task set
+    /// authenticates all requests it makes, with its own OAuth2 token if applicable.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use goose::goose::GooseAuth;
+    ///
+    ///     let mut example_tasks = taskset!("ExampleTasks")
+    ///         .set_auth(GooseAuth::client_credentials("/oauth/token", "client", "secret"));
+    /// ```
+    pub fn set_auth(mut self, auth: GooseAuth) -> Self {
+        trace!("{} set_auth: {:?}", self.name, auth);
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Set a proxy for the task set. If no `--proxy` flag is set when running the load test,
+    /// all requests made by users running this task set are sent through this proxy. SOCKS
+    /// proxies require the `socks` feature.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut example_tasks = taskset!("ExampleTasks").set_proxy("http://10.1.1.1:3128");
+    /// ```
+    pub fn set_proxy(mut self, proxy: &str) -> Self {
+        trace!("{} set_proxy: {}", self.name, proxy);
+        // Proxy validation happens in main() at startup.
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Set a fixed number of users running the task set, regardless of how many users the
+    /// load test runs. The weight of the task set is then ignored, and users are first
+    /// assigned to task sets with fixed and minimum numbers of users. The load test
+    /// fails to start if there aren't enough users.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut admin_tasks = taskset!("AdminTasks").set_fixed_users(1);
+    /// ```
+    pub fn set_fixed_users(mut self, users: usize) -> Self {
+        trace!("{} set_fixed_users: {}", self.name, users);
+        if users < 1 {
+            error!("{} fixed users of {} not allowed", self.name, users);
+            std::process::exit(1);
+        }
+        self.fixed_users = Some(users);
+        self
+    }
+
+    /// Set a minimum number of users running the task set. The task set is assigned this
+    /// many users first, and then its weighted share of the remaining users, so task sets
+    /// with small weights still run when there are few users.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut rare_tasks = taskset!("RareTasks").set_weight(1).set_min_users(1);
+    /// ```
+    pub fn set_min_users(mut self, users: usize) -> Self {
+        trace!("{} set_min_users: {}", self.name, users);
+        if users < 1 {
+            error!("{} minimum users of {} not allowed", self.name, users);
+            std::process::exit(1);
+        }
+        self.min_users = Some(users);
+        self
+    }
+
+    /// Set how many times each user runs the task set, after which the user runs its
+    /// on_stop tasks and exits. Each iteration runs all weighted tasks once. The load test
+    /// stops once all users have exited. The `--iterations` option overrides this.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut seed_tasks = taskset!("SeedTasks").set_iterations(10);
+    /// ```
+    pub fn set_iterations(mut self, iterations: usize) -> Self {
+        trace!("{} set_iterations: {}", self.name, iterations);
+        if iterations < 1 {
+            error!("{} iterations of {} not allowed", self.name, iterations);
+            std::process::exit(1);
+        }
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Add a tag to the task set, which can be called more than once to add multiple tags.
+    /// Tags are inherited by all tasks in the task set, and select which task sets and
+    /// tasks run with the `--tags` and `--exclude-tags` options.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut example_tasks = taskset!("ExampleTasks").set_tag("api").set_tag("smoke");
+    /// ```
+    pub fn set_tag(mut self, tag: &str) -> Self {
+        trace!("{} set_tag: {}", self.name, tag);
+        self.tags.push(tag.to_string());
+        self
+    }
+
     /// Configure a task_set to to pause after running each task. The length of the pause will be randomly
     /// selected from `min_weight` to `max_wait` inclusively.  For example, if `min_wait` is `0` and
     /// `max_weight` is `2`, the user will randomly sleep for 0, 1 or 2 seconds after each task completes.
@@ -427,6 +604,327 @@ impl GooseTaskSet {
     }
 }
 
+/// An authentication provider, adding an `Authorization` header to every request
+/// that doesn't already include one.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum GooseAuth {
+    /// HTTP basic authentication.
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// A static bearer token.
+    Bearer(String),
+    /// An OAuth2 bearer token requested with the client credentials grant.
+    ClientCredentials {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        scope: Option<String>,
+    },
+    /// An OAuth2 bearer token requested with the resource owner password grant.
+    Password {
+        token_url: String,
+        client_id: String,
+        username: String,
+        password: String,
+        scope: Option<String>,
+    },
+}
+impl GooseAuth {
+    /// HTTP basic authentication with a username and password.
+    pub fn basic(username: &str, password: &str) -> Self {
+        GooseAuth::Basic {
+            username: username.to_string(),
+            password: Some(password.to_string()),
+        }
+    }
+
+    /// A static bearer token.
+    pub fn bearer(token: &str) -> Self {
+        GooseAuth::Bearer(token.to_string())
+    }
+
+    /// OAuth2 client credentials grant. A relative `token_url` is requested from the
+    /// user's base_url.
+    pub fn client_credentials(token_url: &str, client_id: &str, client_secret: &str) -> Self {
+        GooseAuth::ClientCredentials {
+            token_url: token_url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            scope: None,
+        }
+    }
+
+    /// OAuth2 resource owner password grant. A relative `token_url` is requested from the
+    /// user's base_url.
+    pub fn password(token_url: &str, client_id: &str, username: &str, password: &str) -> Self {
+        GooseAuth::Password {
+            token_url: token_url.to_string(),
+            client_id: client_id.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            scope: None,
+        }
+    }
+
+    /// Request an OAuth2 token with the specified scope. Has no effect on basic and
+    /// bearer authentication.
+    pub fn set_scope(mut self, new_scope: &str) -> Self {
+        match &mut self {
+            GooseAuth::ClientCredentials { scope, .. } | GooseAuth::Password { scope, .. } => {
+                *scope = Some(new_scope.to_string())
+            }
+            _ => warn!("scope is only supported by oauth2 authentication"),
+        }
+        self
+    }
+}
+
+/// Seconds before an OAuth2 token expires that it is refreshed.
+const AUTH_REFRESH_MARGIN: u64 = 30;
+
+/// Maximum seconds to wait before requesting an OAuth2 token again after a failed token
+/// request. The wait doubles from one second after each consecutive failure.
+const AUTH_RETRY_MAX: u64 = 60;
+
+/// The OAuth2 token response, see RFC 6749 section 5.1.
+#[derive(Debug, Deserialize)]
+struct GooseAuthToken {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+/// The authentication provider of a GooseUser, and its current OAuth2 token.
+#[derive(Debug)]
+pub(crate) struct GooseAuthState {
+    provider: GooseAuth,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires: Option<Instant>,
+    /// Consecutive failed token requests.
+    failures: u32,
+    /// When a token can be requested again after a failed token request.
+    retry_after: Option<Instant>,
+}
+impl GooseAuthState {
+    fn new(provider: GooseAuth) -> Self {
+        GooseAuthState {
+            provider,
+            access_token: None,
+            refresh_token: None,
+            expires: None,
+            failures: 0,
+            retry_after: None,
+        }
+    }
+
+    /// Whether an OAuth2 token needs to be requested or refreshed, which it doesn't
+    /// while backing off after a failed token request.
+    fn needs_token(&self) -> bool {
+        if let Some(retry_after) = self.retry_after {
+            if Instant::now() < retry_after {
+                return false;
+            }
+        }
+        match self.provider {
+            GooseAuth::ClientCredentials { .. } | GooseAuth::Password { .. } => {
+                match (&self.access_token, self.expires) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some(_), Some(expires)) => {
+                        expires <= Instant::now() + Duration::from_secs(AUTH_REFRESH_MARGIN)
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// The form parameters used to request a new OAuth2 token, and the token URL.
+    fn token_request(&self) -> Option<(String, Vec<(&'static str, String)>)> {
+        let (token_url, mut form) = match &self.provider {
+            GooseAuth::ClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+            } => {
+                let mut form = vec![
+                    ("grant_type", "client_credentials".to_string()),
+                    ("client_id", client_id.to_string()),
+                    ("client_secret", client_secret.to_string()),
+                ];
+                if let Some(scope) = scope {
+                    form.push(("scope", scope.to_string()));
+                }
+                (token_url, form)
+            }
+            GooseAuth::Password {
+                token_url,
+                client_id,
+                username,
+                password,
+                scope,
+            } => {
+                let mut form = vec![
+                    ("grant_type", "password".to_string()),
+                    ("client_id", client_id.to_string()),
+                    ("username", username.to_string()),
+                    ("password", password.to_string()),
+                ];
+                if let Some(scope) = scope {
+                    form.push(("scope", scope.to_string()));
+                }
+                (token_url, form)
+            }
+            _ => return None,
+        };
+        // Use the refresh token if the server provided one.
+        if let Some(refresh_token) = &self.refresh_token {
+            form.retain(|(name, _)| *name == "client_id" || *name == "client_secret");
+            form.push(("grant_type", "refresh_token".to_string()));
+            form.push(("refresh_token", refresh_token.to_string()));
+        }
+        Some((token_url.to_string(), form))
+    }
+
+    /// Store a new OAuth2 token, or forget the current token and back off if the request
+    /// failed.
+    fn set_token(&mut self, token: Option<GooseAuthToken>) {
+        match token {
+            Some(token) => {
+                self.failures = 0;
+                self.retry_after = None;
+                self.access_token = Some(token.access_token);
+                self.expires = token
+                    .expires_in
+                    .map(|seconds| Instant::now() + Duration::from_secs(seconds));
+                // Servers may keep using the same refresh token.
+                if token.refresh_token.is_some() {
+                    self.refresh_token = token.refresh_token;
+                }
+            }
+            None => {
+                self.access_token = None;
+                self.refresh_token = None;
+                self.expires = None;
+                let backoff = 2u64.saturating_pow(self.failures).min(AUTH_RETRY_MAX);
+                self.failures += 1;
+                self.retry_after = Some(Instant::now() + Duration::from_secs(backoff));
+            }
+        }
+    }
+
+    /// The value of the Authorization header.
+    fn header(&self) -> Option<String> {
+        match &self.provider {
+            GooseAuth::Basic { username, password } => {
+                let credentials = match password {
+                    Some(password) => format!("{}:{}", username, password),
+                    None => format!("{}:", username),
+                };
+                Some(format!("Basic {}", base64::encode(credentials)))
+            }
+            GooseAuth::Bearer(token) => Some(format!("Bearer {}", token)),
+            _ => self
+                .access_token
+                .as_ref()
+                .map(|token| format!("Bearer {}", token)),
+        }
+    }
+}
+
+/// The HTTP version used when making requests.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "HttpVersionValue")]
+pub enum GooseHttpVersion {
+    /// Negotiate the version with ALPN, preferring HTTP/2. Only the `rustls` TLS backend
+    /// offers HTTP/2, so with the default TLS backend this is always HTTP/1.1.
+    #[serde(rename = "auto")]
+    Auto,
+    /// Only use HTTP/1.1.
+    #[serde(rename = "1.1")]
+    Http1,
+    /// Only use HTTP/2, with prior knowledge that the server supports it. This also
+    /// makes HTTP/2 requests without TLS (h2c).
+    #[serde(rename = "2")]
+    Http2,
+}
+impl FromStr for GooseHttpVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(GooseHttpVersion::Auto),
+            "1.1" => Ok(GooseHttpVersion::Http1),
+            "2" => Ok(GooseHttpVersion::Http2),
+            _ => Err(format!("unknown http version: {}", s)),
+        }
+    }
+}
+/// An HTTP version as set in a configuration file or environment variable, where `2`
+/// and `1.1` can be numbers.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HttpVersionValue {
+    Name(String),
+    Number(f64),
+}
+impl std::convert::TryFrom<HttpVersionValue> for GooseHttpVersion {
+    type Error = String;
+
+    fn try_from(value: HttpVersionValue) -> Result<Self, Self::Error> {
+        match value {
+            HttpVersionValue::Name(name) => GooseHttpVersion::from_str(&name),
+            HttpVersionValue::Number(number) => GooseHttpVersion::from_str(&number.to_string()),
+        }
+    }
+}
+impl fmt::Display for GooseHttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GooseHttpVersion::Auto => write!(f, "auto"),
+            GooseHttpVersion::Http1 => write!(f, "1.1"),
+            GooseHttpVersion::Http2 => write!(f, "2"),
+        }
+    }
+}
+
+/// Whether each user has its own connection pool, or all users share one.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GooseConnectionPool {
+    /// Each user has its own client and connection pool.
+    #[default]
+    User,
+    /// All users share the first user's client and connection pool, see
+    /// [`share_client`](./fn.share_client.html).
+    Shared,
+}
+impl FromStr for GooseConnectionPool {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(GooseConnectionPool::User),
+            "shared" => Ok(GooseConnectionPool::Shared),
+            _ => Err(format!("unknown connection pool: {}", s)),
+        }
+    }
+}
+impl fmt::Display for GooseConnectionPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GooseConnectionPool::User => write!(f, "user"),
+            GooseConnectionPool::Shared => write!(f, "shared"),
+        }
+    }
+}
+
 /// Commands sent between the parent and user threads, and between manager and
 /// worker processes.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -440,7 +938,7 @@ pub enum GooseUserCommand {
 }
 
 /// Supported HTTP methods.
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Serialize, Deserialize, Ord, PartialOrd)]
 pub enum GooseMethod {
     DELETE,
     GET,
@@ -449,6 +947,21 @@ pub enum GooseMethod {
     POST,
     PUT,
 }
+impl FromStr for GooseMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "DELETE" => Ok(GooseMethod::DELETE),
+            "GET" => Ok(GooseMethod::GET),
+            "HEAD" => Ok(GooseMethod::HEAD),
+            "PATCH" => Ok(GooseMethod::PATCH),
+            "POST" => Ok(GooseMethod::POST),
+            "PUT" => Ok(GooseMethod::PUT),
+            _ => Err(format!("unsupported method: {}", s)),
+        }
+    }
+}
 
 fn goose_method_from_method(method: Method) -> GooseMethod {
     match method {
@@ -495,6 +1008,30 @@ pub struct GooseRawRequest {
     pub update: bool,
     /// Which GooseUser thread processed the request.
     pub user: usize,
+    /// How many bytes were sent in the request body.
+    pub request_bytes: u64,
+    /// How many bytes were received in the response body, as transferred on the wire
+    /// (possibly compressed).
+    pub response_bytes: u64,
+    /// How many bytes the decompressed response body contains. This is the same as
+    /// `response_bytes` unless the response was compressed.
+    pub response_decompressed_bytes: u64,
+    /// Whether or not the request is estimated to have re-used an idle connection, see
+    /// [`GooseConnectionTracker`](./struct.GooseConnectionTracker.html).
+    pub connection_reused_estimate: bool,
+    /// The HTTP version of the response (ie, HTTP/1.1, HTTP/2.0), empty if there was no response.
+    pub http_version: String,
+    /// The local address the request was sent from, empty unless `--source-addresses` is set.
+    pub source_address: String,
+    /// The named host the request was made against, empty for the default host.
+    pub host: String,
+    /// The kind of error the request failed with, empty if it didn't fail: one of
+    /// `timeout`, `dns`, `connect`, `tls`, `redirect`, `body`, `decode` or `request` for
+    /// transport errors, `status` followed by the status code for non-2xx responses, or
+    /// `failure` for requests marked as failed with `set_failure`.
+    pub error_kind: String,
+    /// The error message the request failed with, empty if it didn't fail.
+    pub error: String,
 }
 impl GooseRawRequest {
     pub fn new(method: GooseMethod, name: &str, url: &str, elapsed: u128, user: usize) -> Self {
@@ -510,6 +1047,15 @@ impl GooseRawRequest {
             success: true,
             update: false,
             user,
+            request_bytes: 0,
+            response_bytes: 0,
+            response_decompressed_bytes: 0,
+            connection_reused_estimate: false,
+            http_version: "".to_string(),
+            source_address: "".to_string(),
+            host: "".to_string(),
+            error_kind: "".to_string(),
+            error: "".to_string(),
         }
     }
 
@@ -525,6 +1071,24 @@ impl GooseRawRequest {
         self.response_time = response_time as u64;
     }
 
+    fn set_http_version(&mut self, version: Version) {
+        self.http_version = format!("{:?}", version);
+    }
+
+    // Record why the request failed.
+    fn set_error(&mut self, kind: &str, error: &str) {
+        self.error_kind = kind.to_string();
+        self.error = error.to_string();
+    }
+
+    // Record a non-2xx status code as an error.
+    fn set_status_error(&mut self, status_code: StatusCode) {
+        self.set_error(
+            &format!("status {}", status_code.as_u16()),
+            &status_code.to_string(),
+        );
+    }
+
     fn set_status_code(&mut self, status_code: Option<StatusCode>) {
         self.status_code = match status_code {
             Some(status_code) => status_code.as_u16(),
@@ -538,6 +1102,8 @@ impl GooseRawRequest {
 pub struct GooseRequest {
     /// The path for which statistics are being collected.
     pub path: String,
+    /// The named host for which statistics are being collected, empty for the default host.
+    pub host: String,
     /// The method for which statistics are being collected.
     pub method: GooseMethod,
     /// Per-response-time counters, tracking how often pages are returned with this response time.
@@ -556,6 +1122,22 @@ pub struct GooseRequest {
     pub success_count: usize,
     /// Total number of times this path-method request resulted in a non-successful (non-2xx) status code.
     pub fail_count: usize,
+    /// Total number of bytes sent in request bodies.
+    pub request_bytes: usize,
+    /// Total number of response body bytes received on the wire.
+    pub response_bytes: usize,
+    /// Total number of decompressed response body bytes received.
+    pub response_decompressed_bytes: usize,
+    /// Estimated total number of requests that opened a new connection.
+    pub connections_new: usize,
+    /// Estimated total number of requests that re-used an idle connection.
+    pub connections_reused: usize,
+    /// Per-HTTP-version counters, tracking how often each version was used.
+    pub http_version_counts: HashMap<String, usize>,
+    /// Per-source-address counters, tracking how often each `--source-addresses` address was used.
+    pub source_address_counts: HashMap<String, usize>,
+    /// Per-error-kind counters, tracking how often requests failed with each kind of error.
+    pub error_counts: HashMap<String, usize>,
     /// Load test hash.
     pub load_test_hash: u64,
 }
@@ -565,6 +1147,7 @@ impl GooseRequest {
         trace!("new request");
         GooseRequest {
             path: path.to_string(),
+            host: "".to_string(),
             method,
             response_times: BTreeMap::new(),
             min_response_time: 0,
@@ -574,6 +1157,14 @@ impl GooseRequest {
             status_code_counts: HashMap::new(),
             success_count: 0,
             fail_count: 0,
+            request_bytes: 0,
+            response_bytes: 0,
+            response_decompressed_bytes: 0,
+            connections_new: 0,
+            connections_reused: 0,
+            http_version_counts: HashMap::new(),
+            source_address_counts: HashMap::new(),
+            error_counts: HashMap::new(),
             load_test_hash,
         }
     }
@@ -653,6 +1244,71 @@ impl GooseRequest {
         self.status_code_counts.insert(status_code, counter);
         debug!("incremented {} counter: {}", status_code, counter);
     }
+
+    /// Track how many bytes were sent and received.
+    pub fn set_bytes(
+        &mut self,
+        request_bytes: u64,
+        response_bytes: u64,
+        response_decompressed_bytes: u64,
+    ) {
+        self.request_bytes += request_bytes as usize;
+        self.response_bytes += response_bytes as usize;
+        self.response_decompressed_bytes += response_decompressed_bytes as usize;
+    }
+
+    /// Increment counter for HTTP version, ignoring requests that got no response.
+    pub fn set_http_version(&mut self, http_version: &str) {
+        if http_version.is_empty() {
+            return;
+        }
+        let counter = self
+            .http_version_counts
+            .entry(http_version.to_string())
+            .or_insert(0);
+        *counter += 1;
+    }
+
+    /// Increment counter for source address, ignoring requests not bound to one.
+    pub fn set_source_address(&mut self, source_address: &str) {
+        if source_address.is_empty() {
+            return;
+        }
+        let counter = self
+            .source_address_counts
+            .entry(source_address.to_string())
+            .or_insert(0);
+        *counter += 1;
+    }
+
+    /// Increment counter for error kind, ignoring requests that didn't fail.
+    pub fn set_error(&mut self, error_kind: &str) {
+        if error_kind.is_empty() {
+            return;
+        }
+        let counter = self.error_counts.entry(error_kind.to_string()).or_insert(0);
+        *counter += 1;
+    }
+
+    /// Decrement counter for error kind, when a failed request is marked as a success.
+    pub fn unset_error(&mut self, error_kind: &str) {
+        if let Some(counter) = self.error_counts.get_mut(error_kind) {
+            *counter -= 1;
+            if *counter == 0 {
+                self.error_counts.remove(error_kind);
+            }
+        }
+    }
+
+    /// Track whether a new connection was estimated to be opened or an idle connection
+    /// re-used.
+    pub fn set_connection(&mut self, reused: bool) {
+        if reused {
+            self.connections_reused += 1;
+        } else {
+            self.connections_new += 1;
+        }
+    }
 }
 impl Ord for GooseRequest {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -709,96 +1365,586 @@ impl GooseDebug {
     }
 }
 
-/// An individual user state, repeatedly running all GooseTasks in a specific GooseTaskSet.
-#[derive(Debug, Clone)]
-pub struct GooseUser {
-    /// The Instant when this GooseUser client started.
-    pub started: Instant,
-    /// An index into the internal `GooseTest.task_sets` vector, indicating which GooseTaskSet is running.
-    pub task_sets_index: usize,
-    /// Client used to make requests, managing sessions and cookies.
-    pub client: Arc<Mutex<Client>>,
-    /// Integer value tracking the sequenced bucket user is running tasks from.
-    pub weighted_bucket: Arc<AtomicUsize>,
-    /// Integer value tracking the current task user is running.
-    pub weighted_bucket_position: Arc<AtomicUsize>,
-    /// The base URL to prepend to all relative paths.
-    pub base_url: Arc<RwLock<Url>>,
-    /// Minimum amount of time to sleep after running a task.
-    pub min_wait: usize,
-    /// Maximum amount of time to sleep after running a task.
-    pub max_wait: usize,
-    /// A local copy of the global GooseConfiguration.
-    pub config: GooseConfiguration,
-    /// Channel to logger.
-    pub logger: Option<mpsc::UnboundedSender<Option<GooseDebug>>>,
-    /// Channel to parent.
-    pub parent: Option<mpsc::UnboundedSender<GooseRawRequest>>,
-    /// An index into the internal `GooseTest.weighted_users, indicating which weighted GooseTaskSet is running.
-    pub weighted_users_index: usize,
-    /// A weighted list of all tasks that run when the user first starts.
-    pub weighted_on_start_tasks: Vec<Vec<usize>>,
-    /// A weighted list of all tasks that this user runs once started.
-    pub weighted_tasks: Vec<Vec<usize>>,
-    /// A weighted list of all tasks that run when the user stops.
-    pub weighted_on_stop_tasks: Vec<Vec<usize>>,
-    /// Optional name of all requests made within the current task.
-    pub task_request_name: Option<String>,
-    /// Optional name of all requests made within the current task.
-    pub request_name: Option<String>,
-    /// Load test hash.
-    pub load_test_hash: u64,
+/// Reqwest doesn't expose whether a request re-used a pooled connection, so Goose
+/// tracks idle connections the same way the client's connection pool does: each
+/// completed request leaves its connection idle for the next request to the same
+/// host, unless keep-alive is disabled, the server sent `Connection: close`, the
+/// maximum number of idle connections is reached, or the idle timeout expires.
+///
+/// Connection counts are therefore an estimate, and are most accurate for HTTP/1.1.
+/// They are labeled as such in statistics and in the `connection_reused_estimate` field
+/// of the stats log.
+#[derive(Debug)]
+pub struct GooseConnectionTracker {
+    /// Whether or not connections are kept open after a request completes.
+    keepalive: bool,
+    /// Maximum number of idle connections kept open per host.
+    max_idle: usize,
+    /// How long an idle connection is kept open.
+    idle_timeout: Duration,
+    /// When each idle connection was last used, per host.
+    idle: Mutex<HashMap<String, Vec<Instant>>>,
 }
-impl GooseUser {
-    /// Create a new user state.
-    pub fn new(
-        task_sets_index: usize,
-        base_url: Url,
-        min_wait: usize,
-        max_wait: usize,
-        configuration: &GooseConfiguration,
-        load_test_hash: u64,
-    ) -> Self {
-        trace!("new user");
-        match Client::builder()
-            .user_agent(APP_USER_AGENT)
-            .cookie_store(true)
-            .build()
-        {
-            Ok(c) => {
-                GooseUser {
-                    started: Instant::now(),
-                    task_sets_index,
-                    client: Arc::new(Mutex::new(c)),
-                    weighted_bucket: Arc::new(AtomicUsize::new(0)),
-                    weighted_bucket_position: Arc::new(AtomicUsize::new(0)),
-                    base_url: Arc::new(RwLock::new(base_url)),
-                    min_wait,
-                    max_wait,
-                    config: configuration.clone(),
-                    logger: None,
-                    parent: None,
-                    // A value of max_value() indicates this user isn't fully initialized yet.
-                    weighted_users_index: usize::max_value(),
-                    weighted_on_start_tasks: Vec::new(),
-                    weighted_tasks: Vec::new(),
-                    weighted_on_stop_tasks: Vec::new(),
-                    task_request_name: None,
-                    request_name: None,
-                    load_test_hash,
-                }
-            }
-            Err(e) => {
-                error!("failed to create web client: {}", e);
-                std::process::exit(1);
+impl GooseConnectionTracker {
+    /// Create a new connection tracker matching the configured connection pool.
+    pub fn new(configuration: &GooseConfiguration) -> Self {
+        GooseConnectionTracker {
+            keepalive: !configuration.no_keepalive,
+            max_idle: configuration
+                .max_idle_connections
+                .unwrap_or_else(usize::max_value),
+            idle_timeout: Duration::from_secs(configuration.idle_timeout.unwrap_or(90) as u64),
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns true if an idle connection to the host is available, and uses it.
+    fn checkout(&self, host: &str) -> bool {
+        let mut idle = self.idle.lock().unwrap();
+        match idle.get_mut(host) {
+            Some(connections) => {
+                let idle_timeout = self.idle_timeout;
+                connections.retain(|last_used| last_used.elapsed() < idle_timeout);
+                connections.pop().is_some()
             }
+            None => false,
         }
     }
 
-    /// Create a new single-use user.
-    pub fn single(base_url: Url, configuration: &GooseConfiguration) -> Self {
-        let mut single_user = GooseUser::new(0, base_url, 0, 0, configuration, 0);
+    /// Returns a connection to the host to the pool of idle connections.
+    fn checkin(&self, host: &str, response: &Response) {
+        if !self.keepalive {
+            return;
+        }
+        if let Some(connection) = response.headers().get(header::CONNECTION) {
+            if connection.as_bytes().eq_ignore_ascii_case(b"close") {
+                return;
+            }
+        }
+        let mut idle = self.idle.lock().unwrap();
+        let connections = idle.entry(host.to_string()).or_default();
+        if connections.len() < self.max_idle {
+            connections.push(Instant::now());
+        }
+    }
+}
+
+/// Build the client used by a GooseUser, applying the configured connection options.
+fn build_client(
+    configuration: &GooseConfiguration,
+    source_address: Option<IpAddr>,
+) -> Result<Client, Error> {
+    configure_client(
+        Client::builder().user_agent(APP_USER_AGENT),
+        configuration,
+        source_address,
+    )?
+    .build()
+}
+
+/// Apply the connection options set on the command line to a client builder, on top
+/// of the options the builder already sets. Options that aren't set leave the builder
+/// unchanged.
+fn configure_client(
+    builder: ClientBuilder,
+    configuration: &GooseConfiguration,
+    source_address: Option<IpAddr>,
+) -> Result<ClientBuilder, Error> {
+    let mut builder = client_builder(builder);
+    if source_address.is_some() {
+        builder = builder.local_address(source_address);
+    }
+    if !configuration.user_agent.is_empty() {
+        builder = builder.user_agent(configuration.user_agent.as_str());
+    }
+    // Validation of the headers happens in main() at startup.
+    let mut headers = header::HeaderMap::new();
+    for (name, value) in configuration
+        .header
+        .iter()
+        .filter_map(|h| parse_header(h).ok())
+    {
+        headers.append(name, value);
+    }
+    // Compressed responses are decoded by Goose, so it can count the bytes both on
+    // the wire and after decoding.
+    if configuration.gzip && !headers.contains_key(header::ACCEPT_ENCODING) {
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            header::HeaderValue::from_static("gzip, deflate"),
+        );
+    }
+    if !headers.is_empty() {
+        builder = builder.default_headers(headers);
+    }
+    if let Some(timeout) = configuration.timeout {
+        builder = builder.timeout(Duration::from_secs(timeout as u64));
+    }
+    if let Some(connect_timeout) = configuration.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(connect_timeout as u64));
+    }
+    if configuration.accept_invalid_certs {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    // Validation of the proxy happens in main() at startup.
+    if !configuration.proxy.is_empty() {
+        builder = builder.proxy(reqwest::Proxy::all(&configuration.proxy)?);
+    }
+    if configuration.no_keepalive {
+        builder = builder.pool_max_idle_per_host(0);
+    } else if let Some(max_idle_connections) = configuration.max_idle_connections {
+        builder = builder.pool_max_idle_per_host(max_idle_connections);
+    }
+    if let Some(idle_timeout) = configuration.idle_timeout {
+        builder = builder.pool_idle_timeout(Duration::from_secs(idle_timeout as u64));
+    }
+    match configuration.http_version {
+        Some(GooseHttpVersion::Http1) => {
+            builder = http1_only(builder, configuration.accept_invalid_certs)
+        }
+        Some(GooseHttpVersion::Http2) => builder = builder.http2_prior_knowledge(),
+        _ => (),
+    }
+    Ok(builder)
+}
+
+/// Only offer HTTP/1.1 when negotiating TLS. The default TLS backend never offers HTTP/2,
+/// so this is only necessary with the `rustls` TLS backend. A preconfigured TLS backend
+/// ignores `danger_accept_invalid_certs`, so certificate verification is disabled here.
+#[cfg(feature = "rustls")]
+fn http1_only(builder: ClientBuilder, accept_invalid_certs: bool) -> ClientBuilder {
+    let mut tls = rustls_crate::ClientConfig::new();
+    tls.set_protocols(&["http/1.1".into()]);
+    tls.root_store
+        .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+    if accept_invalid_certs {
+        tls.dangerous()
+            .set_certificate_verifier(Arc::new(AcceptInvalidCerts));
+    }
+    builder.use_preconfigured_tls(tls)
+}
+
+#[cfg(not(feature = "rustls"))]
+fn http1_only(builder: ClientBuilder, _accept_invalid_certs: bool) -> ClientBuilder {
+    builder
+}
+
+/// A rustls certificate verifier accepting all certificates, for `--accept-invalid-certs`.
+#[cfg(feature = "rustls")]
+struct AcceptInvalidCerts;
+
+#[cfg(feature = "rustls")]
+impl rustls_crate::ServerCertVerifier for AcceptInvalidCerts {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls_crate::RootCertStore,
+        _presented_certs: &[rustls_crate::Certificate],
+        _dns_name: webpki::DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<rustls_crate::ServerCertVerified, rustls_crate::TLSError> {
+        Ok(rustls_crate::ServerCertVerified::assertion())
+    }
+}
+
+/// Split a `--header` value into a header name and value, for example
+/// `X-Custom-Header: custom value`.
+pub fn parse_header(value: &str) -> Result<(header::HeaderName, header::HeaderValue), String> {
+    let index = match value.find(':') {
+        Some(i) => i,
+        None => return Err(format!("expected 'Name: value', found '{}'", value)),
+    };
+    let name = match header::HeaderName::from_bytes(value[..index].trim().as_bytes()) {
+        Ok(n) => n,
+        Err(e) => return Err(format!("invalid header name in '{}': {}", value, e)),
+    };
+    match header::HeaderValue::from_str(value[index + 1..].trim()) {
+        Ok(v) => Ok((name, v)),
+        Err(e) => Err(format!("invalid header value in '{}': {}", value, e)),
+    }
+}
+
+/// Classify a transport error by kind, to group failed requests in statistics.
+fn get_error_kind(error: &Error) -> &'static str {
+    if error.is_timeout() {
+        return "timeout";
+    }
+    // DNS and TLS errors are only described by the underlying errors, which unlike
+    // the error itself don't include the URL.
+    let mut description = String::new();
+    let mut source = std::error::Error::source(error);
+    while let Some(s) = source {
+        description.push_str(&s.to_string());
+        source = s.source();
+    }
+    let description = description.to_lowercase();
+    if description.contains("dns error") || description.contains("failed to lookup address") {
+        "dns"
+    } else if description.contains("certificate")
+        || description.contains("tls")
+        || description.contains("ssl")
+        || description.contains("handshake")
+    {
+        "tls"
+    } else if error.is_connect() {
+        "connect"
+    } else if error.is_redirect() {
+        "redirect"
+    } else if error.is_body() {
+        "body"
+    } else if error.is_decode() {
+        "decode"
+    } else {
+        "request"
+    }
+}
+
+/// Select one of the `--source-addresses` round-robin by the user's index, if any are
+/// configured, so each user is bound to the same address across runs and workers.
+fn source_address(
+    configuration: &GooseConfiguration,
+    weighted_users_index: usize,
+) -> Option<IpAddr> {
+    if configuration.source_addresses.is_empty() {
+        return None;
+    }
+    // Validation of the source addresses happens in main() at startup.
+    let addresses: Vec<IpAddr> = configuration
+        .source_addresses
+        .split(',')
+        .filter_map(|address| address.trim().parse().ok())
+        .collect();
+    if addresses.is_empty() {
+        return None;
+    }
+    Some(addresses[weighted_users_index % addresses.len()])
+}
+
+/// Goose stores cookies and follows redirects itself, so cookies set while
+/// following a redirect are stored in the GooseUser's cookie jar.
+fn client_builder(builder: ClientBuilder) -> ClientBuilder {
+    builder
+        .cookie_store(false)
+        .redirect(redirect::Policy::none())
+}
+
+/// Prepare the request that follows a redirect response, or return None if the
+/// response is not a redirect that can be followed.
+fn redirect_request(mut request: Request, response: &Response) -> Option<Request> {
+    match response.status() {
+        StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND | StatusCode::SEE_OTHER => {
+            *request.body_mut() = None;
+            for name in &[
+                header::TRANSFER_ENCODING,
+                header::CONTENT_ENCODING,
+                header::CONTENT_TYPE,
+                header::CONTENT_LENGTH,
+            ] {
+                request.headers_mut().remove(name);
+            }
+            if request.method() != Method::GET && request.method() != Method::HEAD {
+                *request.method_mut() = Method::GET;
+            }
+        }
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => (),
+        _ => return None,
+    }
+    let location = response.headers().get(header::LOCATION)?.to_str().ok()?;
+    let url = response.url().join(location).ok()?;
+
+    // Don't send credentials to a different host.
+    let previous = response.url();
+    if url.host_str() != previous.host_str()
+        || url.port_or_known_default() != previous.port_or_known_default()
+    {
+        request.headers_mut().remove(header::AUTHORIZATION);
+        request.headers_mut().remove(header::PROXY_AUTHORIZATION);
+        request.headers_mut().remove(header::WWW_AUTHENTICATE);
+    }
+    // Set the Referer header, unless redirecting from https to http.
+    if !(previous.scheme() == "https" && url.scheme() == "http") {
+        let mut referer = previous.clone();
+        let _ = referer.set_username("");
+        let _ = referer.set_password(None);
+        referer.set_fragment(None);
+        if let Ok(value) = header::HeaderValue::from_str(referer.as_str()) {
+            request.headers_mut().insert(header::REFERER, value);
+        }
+    }
+    *request.url_mut() = url;
+    Some(request)
+}
+
+/// Configure all users to share the first user's client, and therefore also the
+/// connection pool. Each user keeps its own cookie jar, as Goose stores cookies itself
+/// rather than in the client. Invoked when `--connection-pool shared` is set.
+pub fn share_client(users: &mut [GooseUser]) {
+    if let Some(first_user) = users.first() {
+        let client = first_user.client.clone();
+        let connections = first_user.connections.clone();
+        for user in users.iter_mut().skip(1) {
+            user.client = client.clone();
+            user.connections = connections.clone();
+        }
+    }
+}
+
+/// Read the response body, recording how many bytes were received on the wire and
+/// after decoding. Goose decodes compressed responses itself so both sizes are known,
+/// and returns a response with the decoded body for the load test to read.
+async fn read_response(
+    raw_request: &mut GooseRawRequest,
+    response: Response,
+) -> Result<Response, Error> {
+    // Copy everything but the body into a new response before reading the body, so the
+    // original response can be returned if this fails.
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(h) = builder.headers_mut() {
+        *h = response.headers().clone();
+    }
+    let mut rebuilt = match builder.url(response.url().clone()).body(()) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("{}: failed to copy response: {}", &raw_request.url, e);
+            raw_request.success = false;
+            raw_request.set_error("response", &e.to_string());
+            return Ok(response);
+        }
+    };
+    let body = response.bytes().await?;
+    raw_request.response_bytes = body.len() as u64;
+
+    let encoding = rebuilt
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .and_then(|e| e.to_str().ok())
+        .map(|e| e.trim().to_lowercase());
+    let body = match encoding.and_then(|e| decode_body(&e, &body)) {
+        Some(Ok(decoded)) => {
+            // The headers now describe the decoded body.
+            rebuilt.headers_mut().remove(header::CONTENT_ENCODING);
+            rebuilt.headers_mut().remove(header::CONTENT_LENGTH);
+            reqwest::Body::from(decoded)
+        }
+        Some(Err(e)) => {
+            warn!("{}: failed to decode response: {}", &raw_request.url, e);
+            raw_request.success = false;
+            raw_request.set_error("decode", &e.to_string());
+            reqwest::Body::from(body)
+        }
+        None => reqwest::Body::from(body),
+    };
+    raw_request.response_decompressed_bytes = match body.as_bytes() {
+        Some(b) => b.len() as u64,
+        None => raw_request.response_bytes,
+    };
+
+    Ok(Response::from(rebuilt.map(|_| body)))
+}
+
+/// Decode a response body compressed with `encoding`, or return None if the encoding
+/// isn't supported, in which case the body is left as is.
+fn decode_body(encoding: &str, body: &[u8]) -> Option<std::io::Result<Vec<u8>>> {
+    let mut decoded = Vec::new();
+    let result = match encoding {
+        "gzip" | "x-gzip" => GzDecoder::new(body).read_to_end(&mut decoded),
+        "deflate" => ZlibDecoder::new(body).read_to_end(&mut decoded),
+        _ => return None,
+    };
+    Some(result.map(|_| decoded))
+}
+
+/// Build a random number generator for the user with the given index. With a seed, each
+/// user gets its own generator derived from the seed and its index, otherwise the
+/// generator is seeded from the operating system.
+fn build_rng(seed: Option<u64>, weighted_users_index: usize) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(
+            seed ^ (weighted_users_index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        ),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// An individual user state, repeatedly running all GooseTasks in a specific GooseTaskSet.
+#[derive(Debug, Clone)]
+pub struct GooseUser {
+    /// The Instant when this GooseUser client started.
+    pub started: Instant,
+    /// An index into the internal `GooseTest.task_sets` vector, indicating which GooseTaskSet is running.
+    pub task_sets_index: usize,
+    /// Client used to make requests, managing sessions. The lock is only held long
+    /// enough to clone the client, so a user can have multiple requests in flight at
+    /// the same time, all sharing the same cookies and connection pool.
+    pub client: Arc<RwLock<Client>>,
+    /// Tracks idle connections in the client's connection pool.
+    pub connections: Arc<GooseConnectionTracker>,
+    /// The cookie jar, storing cookies set by responses and sending them with requests.
+    pub cookies: Arc<RwLock<CookieStore>>,
+    /// The optional authentication provider, and current OAuth2 token.
+    pub(crate) auth: Arc<RwLock<Option<GooseAuthState>>>,
+    /// The local address requests are sent from, if `--source-addresses` is set.
+    pub source_address: Option<IpAddr>,
+    /// Integer value tracking the sequenced bucket user is running tasks from.
+    pub weighted_bucket: Arc<AtomicUsize>,
+    /// Integer value tracking the current task user is running.
+    pub weighted_bucket_position: Arc<AtomicUsize>,
+    /// The base URL to prepend to all relative paths.
+    pub base_url: Arc<RwLock<Url>>,
+    /// The base URLs of named hosts, set with `--named-host name=host`.
+    pub named_hosts: Arc<RwLock<HashMap<String, Url>>>,
+    /// Rules normalizing request paths into request names, set with `--request-name-rule`.
+    pub(crate) request_name_rules: Arc<Vec<(Regex, String)>>,
+    /// Feeders handing test data records to users, by name.
+    pub(crate) feeders: Arc<HashMap<String, GooseFeeder>>,
+    /// The position of this user in each feeder with the `Sequential` strategy.
+    feeder_positions: Arc<Mutex<HashMap<String, usize>>>,
+    /// The load test's own command line options, see `GooseAttack::initialize_with_options`.
+    pub(crate) options: Option<Arc<dyn Any + Send + Sync>>,
+    /// The user's random number generator, seeded from `--seed` and the user's index if set.
+    pub(crate) rng: Arc<Mutex<StdRng>>,
+    /// The requests replayed by users with `--replay`.
+    pub(crate) replay: Option<Arc<GooseReplay>>,
+    /// The pages of the user's task set, if it was built from a HAR file.
+    pub(crate) har: Option<Arc<GooseHarPages>>,
+    /// The feeder records the user substitutes into HAR requests, by feeder name.
+    pub(crate) har_records: Arc<Mutex<HashMap<String, GooseRecord>>>,
+    /// Minimum amount of time to sleep after running a task.
+    pub min_wait: usize,
+    /// Maximum amount of time to sleep after running a task.
+    pub max_wait: usize,
+    /// A local copy of the global GooseConfiguration.
+    pub config: GooseConfiguration,
+    /// Channel to logger.
+    pub logger: Option<mpsc::UnboundedSender<Option<GooseDebug>>>,
+    /// Channel to parent.
+    pub parent: Option<mpsc::UnboundedSender<GooseRawRequest>>,
+    /// An index into the internal `GooseTest.weighted_users, indicating which weighted GooseTaskSet is running.
+    pub weighted_users_index: usize,
+    /// An index into `GooseTaskSet.tasks`, indicating which task is running.
+    pub(crate) tasks_index: usize,
+    /// A weighted list of all tasks that run when the user first starts.
+    pub weighted_on_start_tasks: Vec<Vec<usize>>,
+    /// A weighted list of all tasks that this user runs once started.
+    pub weighted_tasks: Vec<Vec<usize>>,
+    /// A weighted list of all tasks that run when the user stops.
+    pub weighted_on_stop_tasks: Vec<Vec<usize>>,
+    /// Optional name of all requests made within the current task.
+    pub task_request_name: Option<String>,
+    /// Optional name of all requests made within the current task.
+    pub request_name: Option<String>,
+    /// Load test hash.
+    pub load_test_hash: u64,
+}
+impl GooseUser {
+    /// Create a new user state.
+    pub fn new(
+        task_sets_index: usize,
+        base_url: Url,
+        min_wait: usize,
+        max_wait: usize,
+        configuration: &GooseConfiguration,
+        load_test_hash: u64,
+    ) -> Self {
+        trace!("new user");
+        // Users are bound to a source address once their index is known.
+        match build_client(configuration, None) {
+            Ok(c) => {
+                GooseUser {
+                    started: Instant::now(),
+                    task_sets_index,
+                    client: Arc::new(RwLock::new(c)),
+                    connections: Arc::new(GooseConnectionTracker::new(configuration)),
+                    cookies: Arc::new(RwLock::new(CookieStore::default())),
+                    auth: Arc::new(RwLock::new(None)),
+                    source_address: None,
+                    weighted_bucket: Arc::new(AtomicUsize::new(0)),
+                    weighted_bucket_position: Arc::new(AtomicUsize::new(0)),
+                    base_url: Arc::new(RwLock::new(base_url)),
+                    named_hosts: Arc::new(RwLock::new(get_named_hosts(configuration))),
+                    request_name_rules: Arc::new(Vec::new()),
+                    feeders: Arc::new(HashMap::new()),
+                    feeder_positions: Arc::new(Mutex::new(HashMap::new())),
+                    options: None,
+                    rng: Arc::new(Mutex::new(build_rng(configuration.seed, usize::MAX))),
+                    replay: None,
+                    har: None,
+                    har_records: Arc::new(Mutex::new(HashMap::new())),
+                    min_wait,
+                    max_wait,
+                    config: configuration.clone(),
+                    logger: None,
+                    parent: None,
+                    // A value of max_value() indicates this user isn't fully initialized yet.
+                    weighted_users_index: usize::max_value(),
+                    tasks_index: 0,
+                    weighted_on_start_tasks: Vec::new(),
+                    weighted_tasks: Vec::new(),
+                    weighted_on_stop_tasks: Vec::new(),
+                    task_request_name: None,
+                    request_name: None,
+                    load_test_hash,
+                }
+            }
+            Err(e) => {
+                error!("failed to create web client: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Bind the user to one of the `--source-addresses` by its index, if any are
+    /// configured, rebuilding its client. Called once the user's index is known.
+    pub(crate) fn init_source_address(&mut self) {
+        let address = match source_address(&self.config, self.weighted_users_index) {
+            Some(a) => a,
+            None => return,
+        };
+        debug!(
+            "binding user {} to source address {}",
+            self.weighted_users_index + 1,
+            address
+        );
+        match build_client(&self.config, Some(address)) {
+            Ok(c) => {
+                self.client = Arc::new(RwLock::new(c));
+                self.source_address = Some(address);
+            }
+            Err(e) => {
+                error!("failed to create web client: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Give the user its own cookie jar, pre-seeded with the cookies loaded from
+    /// `--cookies-file`, before it starts.
+    pub(crate) fn init_cookies(&mut self, seed: &[(Url, GooseCookie)]) {
+        self.cookies = Arc::new(RwLock::new(cookies::build_cookie_jar(seed)));
+    }
+
+    /// Configure the authentication provider of the user's task set, before it starts.
+    pub(crate) fn init_auth(&mut self, auth: &Option<GooseAuth>) {
+        let state = auth.clone().map(GooseAuthState::new);
+        self.auth = Arc::new(RwLock::new(state));
+    }
+
+    /// Give the user a new random number generator, seeded from `--seed` and the user's
+    /// index if set. Called once the user's index is known.
+    pub(crate) fn init_rng(&mut self) {
+        self.rng = Arc::new(Mutex::new(build_rng(
+            self.config.seed,
+            self.weighted_users_index,
+        )));
+    }
+
+    /// Create a new single-use user.
+    pub fn single(base_url: Url, configuration: &GooseConfiguration) -> Self {
+        let mut single_user = GooseUser::new(0, base_url, 0, 0, configuration, 0);
         single_user.weighted_users_index = 0;
+        single_user.init_rng();
+        single_user.init_source_address();
         single_user
     }
 
@@ -830,6 +1976,120 @@ impl GooseUser {
         }
     }
 
+    /// A helper that prepends the base_url of a named host to all relative paths.
+    /// Named hosts are set on the command line with `--named-host name=host`, for example
+    /// `--named-host api=https://api.example.com/`.
+    pub async fn build_url_on(&self, host: &str, path: &str) -> String {
+        // If URL includes a host, simply use it.
+        if let Ok(parsed_path) = Url::parse(path) {
+            if let Some(_host) = parsed_path.host() {
+                return path.to_string();
+            }
+        }
+        let named_hosts = self.named_hosts.read().await;
+        let base_url = match named_hosts.get(host) {
+            Some(b) => b,
+            None => {
+                error!(
+                    "no --named-host {}= configured for path {} for task {}",
+                    &host, &path, self.task_sets_index
+                );
+                std::process::exit(1);
+            }
+        };
+        match base_url.join(path) {
+            Ok(url) => url.to_string(),
+            Err(e) => {
+                error!(
+                    "failed to build url from base {:?} and path {} for task {}: {}",
+                    &base_url, &path, self.task_sets_index, e
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Get the next record of test data from a feeder registered with
+    /// `GooseAttack::register_feeder`. Returns `None` if the feeder has no records, or
+    /// if all records of a feeder with the `Unique` strategy have been handed out.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(search).set_on_start();
+    ///
+    ///     /// Search for a term from the terms feeder.
+    ///     async fn search(user: &GooseUser) {
+    ///       if let Some(record) = user.feed("terms") {
+    ///         let _response = user.get(&format!("/search?q={}", record["term"])).await;
+    ///       }
+    ///     }
+    /// ```
+    pub fn feed(&self, name: &str) -> Option<GooseRecord> {
+        let feeder = match self.feeders.get(name) {
+            Some(f) => f,
+            None => {
+                error!("no feeder named {} is registered", name);
+                std::process::exit(1);
+            }
+        };
+        let mut feeder_positions = self.feeder_positions.lock().unwrap();
+        let position = feeder_positions.entry(name.to_string()).or_insert(0);
+        feeder.next_record(position, &mut *self.rng())
+    }
+
+    /// Get the user's random number generator, for tasks to make their own random
+    /// choices. Goose also uses it to shuffle the order tasks run in and to pick wait
+    /// times, so with `--seed` set each user makes the same choices every run. The
+    /// generator is locked until the returned guard is dropped, so don't hold it across
+    /// an `.await`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use rand::Rng;
+    ///
+    ///     let mut task = task!(get_node);
+    ///
+    ///     async fn get_node(user: &GooseUser) {
+    ///         let node_id = user.rng().gen_range(1, 10_000);
+    ///         let _response = user.get(&format!("/node/{}", node_id)).await;
+    ///     }
+    /// ```
+    pub fn rng(&self) -> MutexGuard<'_, StdRng> {
+        self.rng.lock().unwrap()
+    }
+
+    /// Get the load test's own command line options, parsed by
+    /// `GooseAttack::initialize_with_options()`. Returns `None` if the load test was
+    /// initialized without options, or with options of another type.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use structopt::StructOpt;
+    ///
+    ///     #[derive(StructOpt)]
+    ///     struct LoadTestOptions {
+    ///         /// Tenant to load test
+    ///         #[structopt(long, default_value = "default")]
+    ///         tenant: String,
+    ///     }
+    ///
+    ///     let mut task = task!(get_tenant);
+    ///
+    ///     /// Load the home page of the tenant set with --tenant.
+    ///     async fn get_tenant(user: &GooseUser) {
+    ///       if let Some(options) = user.get_options::<LoadTestOptions>() {
+    ///         let _response = user.get(&format!("/{}/", options.tenant)).await;
+    ///       }
+    ///     }
+    /// ```
+    pub fn get_options<T: Any>(&self) -> Option<&T> {
+        self.options.as_ref().and_then(|o| o.downcast_ref::<T>())
+    }
+
     /// A helper to make a `GET` request of a path and collect relevant statistics.
     /// Automatically prepends the correct host.
     ///
@@ -859,6 +2119,25 @@ impl GooseUser {
         self.goose_send(request_builder, None).await
     }
 
+    /// A helper to make a `GET` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(get_function);
+    ///
+    ///     /// A very simple task that makes a GET request of the api host.
+    ///     async fn get_function(user: &GooseUser) {
+    ///       let _response = user.get_on("api", "/v1/items");
+    ///     }
+    /// ```
+    pub async fn get_on(&self, host: &str, path: &str) -> GooseResponse {
+        let request_builder = self.goose_get_on(host, path).await;
+        self.goose_send_on(host, request_builder, None).await
+    }
+
     /// A helper to make a named `GET` request of a path and collect relevant statistics.
     /// Automatically prepends the correct host. Naming a request only affects collected
     /// statistics.
@@ -913,6 +2192,25 @@ impl GooseUser {
         self.goose_send(request_builder, None).await
     }
 
+    /// A helper to make a `POST` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(post_function);
+    ///
+    ///     /// A very simple task that makes a POST request of the api host.
+    ///     async fn post_function(user: &GooseUser) {
+    ///       let _response = user.post_on("api", "/v1/items", "BODY BEING POSTED");
+    ///     }
+    /// ```
+    pub async fn post_on(&self, host: &str, path: &str, body: &str) -> GooseResponse {
+        let request_builder = self.goose_post_on(host, path).await.body(body.to_string());
+        self.goose_send_on(host, request_builder, None).await
+    }
+
     /// A helper to make a named `POST` request of a path and collect relevant statistics.
     /// Automatically prepends the correct host. Naming a request only affects collected
     /// statistics.
@@ -967,6 +2265,25 @@ impl GooseUser {
         self.goose_send(request_builder, None).await
     }
 
+    /// A helper to make a `HEAD` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(head_function);
+    ///
+    ///     /// A very simple task that makes a HEAD request of the cdn host.
+    ///     async fn head_function(user: &GooseUser) {
+    ///       let _response = user.head_on("cdn", "/logo.png");
+    ///     }
+    /// ```
+    pub async fn head_on(&self, host: &str, path: &str) -> GooseResponse {
+        let request_builder = self.goose_head_on(host, path).await;
+        self.goose_send_on(host, request_builder, None).await
+    }
+
     /// A helper to make a named `HEAD` request of a path and collect relevant statistics.
     /// Automatically prepends the correct host. Naming a request only affects collected
     /// statistics.
@@ -1021,6 +2338,63 @@ impl GooseUser {
         self.goose_send(request_builder, None).await
     }
 
+    /// A helper to make a `DELETE` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(delete_function);
+    ///
+    ///     /// A very simple task that makes a DELETE request of the api host.
+    ///     async fn delete_function(user: &GooseUser) {
+    ///       let _response = user.delete_on("api", "/v1/items/1");
+    ///     }
+    /// ```
+    pub async fn delete_on(&self, host: &str, path: &str) -> GooseResponse {
+        let request_builder = self.goose_delete_on(host, path).await;
+        self.goose_send_on(host, request_builder, None).await
+    }
+
+    /// A helper to make a `PUT` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(put_function);
+    ///
+    ///     /// A very simple task that makes a PUT request of the api host.
+    ///     async fn put_function(user: &GooseUser) {
+    ///       let _response = user.put_on("api", "/v1/items/1", "BODY BEING SENT");
+    ///     }
+    /// ```
+    pub async fn put_on(&self, host: &str, path: &str, body: &str) -> GooseResponse {
+        let request_builder = self.goose_put_on(host, path).await.body(body.to_string());
+        self.goose_send_on(host, request_builder, None).await
+    }
+
+    /// A helper to make a `PATCH` request of a path on a named host and collect relevant
+    /// statistics. Prepends the base_url of the named host, set with `--named-host name=host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(patch_function);
+    ///
+    ///     /// A very simple task that makes a PATCH request of the api host.
+    ///     async fn patch_function(user: &GooseUser) {
+    ///       let _response = user.patch_on("api", "/v1/items/1", "BODY BEING SENT");
+    ///     }
+    /// ```
+    pub async fn patch_on(&self, host: &str, path: &str, body: &str) -> GooseResponse {
+        let request_builder = self.goose_patch_on(host, path).await.body(body.to_string());
+        self.goose_send_on(host, request_builder, None).await
+    }
+
     /// A helper to make a named `DELETE` request of a path and collect relevant statistics.
     /// Automatically prepends the correct host. Naming a request only affects collected
     /// statistics.
@@ -1067,7 +2441,7 @@ impl GooseUser {
     /// ```
     pub async fn goose_get(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.get(&url)
+        self.client.read().await.get(&url)
     }
 
     /// Prepends the correct host on the path, then prepares a
@@ -1091,7 +2465,7 @@ impl GooseUser {
     /// ```
     pub async fn goose_post(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.post(&url)
+        self.client.read().await.post(&url)
     }
 
     /// Prepends the correct host on the path, then prepares a
@@ -1115,7 +2489,7 @@ impl GooseUser {
     /// ```
     pub async fn goose_head(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.head(&url)
+        self.client.read().await.head(&url)
     }
 
     /// Prepends the correct host on the path, then prepares a
@@ -1139,7 +2513,7 @@ impl GooseUser {
     /// ```
     pub async fn goose_put(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.put(&url)
+        self.client.read().await.put(&url)
     }
 
     /// Prepends the correct host on the path, then prepares a
@@ -1163,7 +2537,7 @@ impl GooseUser {
     /// ```
     pub async fn goose_patch(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.patch(&url)
+        self.client.read().await.patch(&url)
     }
 
     /// Prepends the correct host on the path, then prepares a
@@ -1187,7 +2561,163 @@ impl GooseUser {
     /// ```
     pub async fn goose_delete(&self, path: &str) -> RequestBuilder {
         let url = self.build_url(path).await;
-        self.client.lock().await.delete(&url)
+        self.client.read().await.delete(&url)
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `GET` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(get_function);
+    ///
+    ///     /// A simple task that makes a GET request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn get_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_get_on("api", "/v1/items").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_get_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::GET, host, path).await
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `POST` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(post_function);
+    ///
+    ///     /// A simple task that makes a POST request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn post_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_post_on("api", "/v1/items").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_post_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::POST, host, path).await
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `HEAD` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(head_function);
+    ///
+    ///     /// A simple task that makes a HEAD request of the cdn host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn head_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_head_on("cdn", "/logo.png").await;
+    ///       let response = user.goose_send_on("cdn", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_head_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::HEAD, host, path).await
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `PUT` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(put_function);
+    ///
+    ///     /// A simple task that makes a PUT request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn put_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_put_on("api", "/v1/items/1").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_put_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::PUT, host, path).await
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `PATCH` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(patch_function);
+    ///
+    ///     /// A simple task that makes a PATCH request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn patch_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_patch_on("api", "/v1/items/1").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_patch_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::PATCH, host, path).await
+    }
+
+    /// Prepends the base_url of a named host on the path, then prepares a
+    /// [`reqwest::RequestBuilder`](https://docs.rs/reqwest/*/reqwest/struct.RequestBuilder.html)
+    /// object for making a `DELETE` request. Statistics of the request are grouped under
+    /// the named host.
+    ///
+    /// (You must then call `goose_send_on` with the same named host on this object to
+    /// actually execute the request.)
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(delete_function);
+    ///
+    ///     /// A simple task that makes a DELETE request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn delete_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_delete_on("api", "/v1/items/1").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_delete_on(&self, host: &str, path: &str) -> RequestBuilder {
+        self.goose_request_on(Method::DELETE, host, path).await
+    }
+
+    /// Prepares a request against a named host.
+    async fn goose_request_on(&self, method: Method, host: &str, path: &str) -> RequestBuilder {
+        let url = self.build_url_on(host, path).await;
+        self.client.read().await.request(method, &url)
     }
 
     /// Builds the provided
@@ -1204,6 +2734,10 @@ impl GooseUser {
     /// ([`response.request`](https://docs.rs/goose/*/goose/goose/struct.GooseRawRequest)), and the response
     /// ([`response.response`](https://docs.rs/reqwest/*/reqwest/struct.Response.html)).
     ///
+    /// A single user can have multiple requests in flight at the same time, for example
+    /// by awaiting several requests with `futures::join!`. All of them share the user's
+    /// cookies and connection pool.
+    ///
     /// # Example
     /// ```rust
     ///     use goose::prelude::*;
@@ -1221,9 +2755,47 @@ impl GooseUser {
         &self,
         request_builder: RequestBuilder,
         request_name: Option<&str>,
+    ) -> GooseResponse {
+        self.send_request(request_builder, request_name, None).await
+    }
+
+    /// Builds and executes a request prepared for a named host, such as with
+    /// `goose_get_on`, as `goose_send` does, grouping its statistics under the named
+    /// host. Sticky-follow redirects change the base_url of the named host.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(get_function);
+    ///
+    ///     /// A simple task that makes a GET request of the api host, exposing the
+    ///     /// Reqwest request builder.
+    ///     async fn get_function(user: &GooseUser) {
+    ///       let request_builder = user.goose_get_on("api", "/v1/items").await;
+    ///       let response = user.goose_send_on("api", request_builder, None).await;
+    ///     }
+    /// ```
+    pub async fn goose_send_on(
+        &self,
+        host: &str,
+        request_builder: RequestBuilder,
+        request_name: Option<&str>,
+    ) -> GooseResponse {
+        self.send_request(request_builder, request_name, Some(host))
+            .await
+    }
+
+    /// Builds and executes a request, capturing its statistics, and grouping them under
+    /// the named host if any.
+    async fn send_request(
+        &self,
+        request_builder: RequestBuilder,
+        request_name: Option<&str>,
+        named_host: Option<&str>,
     ) -> GooseResponse {
         let started = Instant::now();
-        let request = match request_builder.build() {
+        let mut request = match request_builder.build() {
             Ok(r) => r,
             Err(e) => {
                 error!("goose_send failed to build request: {}", e);
@@ -1248,10 +2820,54 @@ impl GooseUser {
             self.started.elapsed().as_millis(),
             self.weighted_users_index,
         );
+        if let Some(address) = self.source_address {
+            raw_request.source_address = address.to_string();
+        }
+        if let Some(host) = named_host {
+            raw_request.host = host.to_string();
+        }
+        // Streamed bodies have an unknown length and are not counted.
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            raw_request.request_bytes = body.len() as u64;
+        }
 
-        // Make the actual request.
-        let response = self.client.lock().await.execute(request).await;
+        // Connections are pooled per scheme, host and port.
+        let host = request.url()[..url::Position::BeforePath].to_string();
+        raw_request.connection_reused_estimate = self.connections.checkout(&host);
+
+        // Make the actual request. Cloning the client is cheap and shares the connection
+        // pool, and releases the lock before the request is made.
+        let client = self.client.read().await.clone();
+        self.add_auth_header(&mut request).await;
+        let max_redirects = self.config.max_redirects.unwrap_or(MAX_REDIRECTS);
+        let mut redirects = 0;
+        let response = loop {
+            // Keep a copy without cookies in case the response is a redirect. Requests
+            // with a streamed body can't be copied, and their redirects aren't followed.
+            let next_request = request.try_clone();
+            self.add_cookie_header(&mut request).await;
+            let response = client.execute(request).await;
+            let redirect = match &response {
+                Ok(r) => {
+                    self.store_cookies(r).await;
+                    next_request.and_then(|next| redirect_request(next, r))
+                }
+                Err(_) => None,
+            };
+            match redirect {
+                Some(next) if redirects < max_redirects => {
+                    debug!("{:?}: following redirect to {}", &path, next.url());
+                    redirects += 1;
+                    request = next;
+                }
+                _ => break response,
+            }
+        };
         raw_request.set_response_time(started.elapsed().as_millis());
+        let response = match response {
+            Ok(r) => read_response(&mut raw_request, r).await,
+            Err(e) => Err(e),
+        };
 
         match &response {
             Ok(r) => {
@@ -1260,47 +2876,351 @@ impl GooseUser {
                 // @TODO: match/handle all is_foo() https://docs.rs/http/0.2.1/http/status/struct.StatusCode.html
                 if !status_code.is_success() {
                     raw_request.success = false;
+                    raw_request.set_status_error(status_code);
                 }
                 raw_request.set_status_code(Some(status_code));
+                raw_request.set_http_version(r.version());
+                raw_request.set_final_url(r.url().as_str());
+                self.connections.checkin(&host, r);
+
+                // Load test user was redirected, each named host follows redirects separately.
+                if self.config.sticky_follow && raw_request.url != raw_request.final_url {
+                    let base_url = if raw_request.host.is_empty() {
+                        self.base_url.read().await.to_string()
+                    } else {
+                        match self.named_hosts.read().await.get(&raw_request.host) {
+                            Some(b) => b.to_string(),
+                            None => "".to_string(),
+                        }
+                    };
+                    // Check if the URL redirected started with the load test base_url.
+                    if !raw_request.final_url.starts_with(&base_url) {
+                        let redirected_base_url = match Url::parse(&raw_request.final_url) {
+                            // Use URL to grab base_url, which is everything up to the path.
+                            Ok(base_url) => base_url[..url::Position::BeforePath].to_string(),
+                            Err(e) => {
+                                error!("goose_send failed to parse redirected URL: {}", e);
+                                std::process::exit(1);
+                            }
+                        };
+                        if raw_request.host.is_empty() {
+                            info!(
+                                "base_url for user {} redirected from {} to {}",
+                                self.weighted_users_index + 1,
+                                &base_url,
+                                &redirected_base_url
+                            );
+                            self.set_base_url(&redirected_base_url).await;
+                        } else {
+                            info!(
+                                "{} base_url for user {} redirected from {} to {}",
+                                &raw_request.host,
+                                self.weighted_users_index + 1,
+                                &base_url,
+                                &redirected_base_url
+                            );
+                            self.set_named_base_url(&raw_request.host, &redirected_base_url)
+                                .await;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                let kind = get_error_kind(e);
+                warn!("{:?}: {} error: {}", &path, kind, e);
+                raw_request.success = false;
+                raw_request.set_status_code(None);
+                raw_request.set_error(kind, &e.to_string());
+            }
+        };
+
+        // Send raw request object to parent if we're tracking statistics.
+        if !self.config.no_stats {
+            self.send_to_parent(&raw_request);
+        }
+
+        GooseResponse::new(raw_request, response)
+    }
+
+    /// Add the Authorization header of the user's authentication provider to the request,
+    /// unless the request already includes one. OAuth2 tokens are requested when needed,
+    /// holding the write lock so concurrent requests wait for the same token. After a
+    /// failed token request, requests are made without a token until the user retries.
+    async fn add_auth_header(&self, request: &mut Request) {
+        if request.headers().contains_key(header::AUTHORIZATION) {
+            return;
+        }
+        // Only take the write lock if a token needs to be requested.
+        let current = match self.auth.read().await.as_ref() {
+            Some(state) if !state.needs_token() => Some(state.header()),
+            Some(_) => None,
+            None => return,
+        };
+        let header = match current {
+            Some(header) => header,
+            None => {
+                let mut auth = self.auth.write().await;
+                match auth.as_mut() {
+                    Some(state) => {
+                        // Another request may have requested a token while waiting.
+                        if state.needs_token() {
+                            if let Some((token_url, form)) = state.token_request() {
+                                let token = self.request_token(&token_url, &form).await;
+                                state.set_token(token);
+                            }
+                        }
+                        state.header()
+                    }
+                    None => None,
+                }
+            }
+        };
+        if let Some(value) = header {
+            match header::HeaderValue::from_str(&value) {
+                Ok(v) => {
+                    request.headers_mut().insert(header::AUTHORIZATION, v);
+                }
+                Err(e) => warn!("invalid authorization header: {}", e),
+            }
+        }
+    }
+
+    /// Request an OAuth2 token. Token requests are recorded under the "auth" request name.
+    async fn request_token(
+        &self,
+        token_url: &str,
+        form: &[(&'static str, String)],
+    ) -> Option<GooseAuthToken> {
+        let started = Instant::now();
+        let url = self.build_url(token_url).await;
+        let mut raw_request = GooseRawRequest::new(
+            GooseMethod::POST,
+            "auth",
+            &url,
+            self.started.elapsed().as_millis(),
+            self.weighted_users_index,
+        );
+        if let Some(address) = self.source_address {
+            raw_request.source_address = address.to_string();
+        }
+        let client = self.client.read().await.clone();
+        let response = client.post(&url).form(form).send().await;
+        raw_request.set_response_time(started.elapsed().as_millis());
+        let response = match response {
+            Ok(r) => read_response(&mut raw_request, r).await,
+            Err(e) => Err(e),
+        };
+
+        let token = match response {
+            Ok(r) => {
+                raw_request.set_status_code(Some(r.status()));
+                raw_request.set_http_version(r.version());
                 raw_request.set_final_url(r.url().as_str());
+                if r.status().is_success() {
+                    match r.json::<GooseAuthToken>().await {
+                        Ok(t) => Some(t),
+                        Err(e) => {
+                            warn!("invalid auth token response from {}: {}", &url, e);
+                            raw_request.set_error(get_error_kind(&e), &e.to_string());
+                            None
+                        }
+                    }
+                } else {
+                    warn!("auth token request to {} failed: {}", &url, r.status());
+                    raw_request.set_status_error(r.status());
+                    None
+                }
+            }
+            Err(e) => {
+                warn!("auth token request to {} failed: {}", &url, e);
+                raw_request.set_status_code(None);
+                raw_request.set_error(get_error_kind(&e), &e.to_string());
+                None
+            }
+        };
+        raw_request.success = token.is_some();
+
+        if !self.config.no_stats {
+            self.send_to_parent(&raw_request);
+        }
+        token
+    }
+
+    /// Sets the authentication provider used by this user, replacing the provider of the
+    /// user's task set if any. The header is added to all subsequent requests that don't
+    /// already include an `Authorization` header.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use goose::goose::GooseAuth;
+    ///
+    ///     let mut task = task!(login).set_on_start();
+    ///
+    ///     async fn login(user: &GooseUser) {
+    ///       let username = format!("user{}", user.weighted_users_index);
+    ///       user.set_auth(GooseAuth::basic(&username, "secret")).await;
+    ///     }
+    /// ```
+    pub async fn set_auth(&self, auth: GooseAuth) {
+        *self.auth.write().await = Some(GooseAuthState::new(auth));
+    }
+
+    /// Add cookies from the cookie jar to the request, unless the request already
+    /// includes a Cookie header.
+    async fn add_cookie_header(&self, request: &mut Request) {
+        if request.headers().contains_key(header::COOKIE) {
+            return;
+        }
+        let cookie_header = self
+            .cookies
+            .read()
+            .await
+            .get_request_cookies(request.url())
+            .map(|c| format!("{}={}", c.name(), c.value()))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if !cookie_header.is_empty() {
+            if let Ok(value) = header::HeaderValue::from_str(&cookie_header) {
+                request.headers_mut().insert(header::COOKIE, value);
+            }
+        }
+    }
+
+    /// Store cookies set by the response in the cookie jar.
+    async fn store_cookies(&self, response: &Response) {
+        let cookies: Vec<GooseCookie> = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| Cookie::parse(value.to_string()).ok())
+            .collect();
+        if !cookies.is_empty() {
+            self.cookies
+                .write()
+                .await
+                .store_response_cookies(cookies.into_iter(), response.url());
+        }
+    }
+
+    /// Returns all cookies in the user's cookie jar for the specified domain. The domain
+    /// and path of each returned cookie is set, so it can be used to find and delete it.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(list_cookies);
+    ///
+    ///     async fn list_cookies(user: &GooseUser) {
+    ///       for cookie in user.get_cookies("example.com").await {
+    ///         println!("{}={}", cookie.name(), cookie.value());
+    ///       }
+    ///     }
+    /// ```
+    pub async fn get_cookies(&self, domain: &str) -> Vec<GooseCookie> {
+        self.cookies
+            .read()
+            .await
+            .iter_unexpired()
+            .filter(|c| c.domain.as_cow().as_deref() == Some(domain))
+            .map(|c| {
+                let path = String::from(&c.path);
+                let mut cookie: GooseCookie = c.clone().into();
+                cookie.set_domain(domain.to_string());
+                cookie.set_path(path);
+                cookie
+            })
+            .collect()
+    }
+
+    /// Returns the named cookie from the user's cookie jar for the specified domain, if
+    /// set. This is useful for example to copy a session cookie into a request header.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(session_header);
+    ///
+    ///     async fn session_header(user: &GooseUser) {
+    ///       if let Some(session) = user.get_cookie("example.com", "session").await {
+    ///         let request_builder = user.goose_get("/api/").await
+    ///           .header("X-Session", session.value());
+    ///         let _response = user.goose_send(request_builder, None).await;
+    ///       }
+    ///     }
+    /// ```
+    pub async fn get_cookie(&self, domain: &str, name: &str) -> Option<GooseCookie> {
+        self.get_cookies(domain)
+            .await
+            .into_iter()
+            .find(|c| c.name() == name)
+    }
 
-                // Load test user was redirected.
-                if self.config.sticky_follow && raw_request.url != raw_request.final_url {
-                    let base_url = self.base_url.read().await.to_string();
-                    // Check if the URL redirected started with the load test base_url.
-                    if !raw_request.final_url.starts_with(&base_url) {
-                        let redirected_base_url = match Url::parse(&raw_request.final_url) {
-                            // Use URL to grab base_url, which is everything up to the path.
-                            Ok(base_url) => base_url[..url::Position::BeforePath].to_string(),
-                            Err(e) => {
-                                error!("goose_send failed to parse redirected URL: {}", e);
-                                std::process::exit(1);
-                            }
-                        };
-                        info!(
-                            "base_url for user {} redirected from {} to {}",
-                            self.weighted_users_index + 1,
-                            &base_url,
-                            &redirected_base_url
-                        );
-                        self.set_base_url(&redirected_base_url).await;
-                    }
-                }
-            }
-            Err(e) => {
-                // @TODO: what can we learn from a reqwest error?
-                warn!("{:?}: {}", &path, e);
-                raw_request.success = false;
-                raw_request.set_status_code(None);
-            }
+    /// Stores a cookie in the user's cookie jar, as if it was set by the specified
+    /// domain. Without a domain attribute the cookie is only sent to that exact domain.
+    /// Returns an error if the domain is invalid, or the cookie can't be set by it.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///     use goose::goose::GooseCookie;
+    ///
+    ///     let mut task = task!(set_cookie).set_on_start();
+    ///
+    ///     async fn set_cookie(user: &GooseUser) {
+    ///       if let Err(e) = user.set_cookie("example.com", GooseCookie::new("consent", "yes")).await {
+    ///         eprintln!("failed to set cookie: {}", e);
+    ///       }
+    ///     }
+    /// ```
+    pub async fn set_cookie(&self, domain: &str, cookie: GooseCookie) -> Result<(), String> {
+        let scheme = if cookie.secure() == Some(true) {
+            "https"
+        } else {
+            "http"
         };
+        let path = cookie.path().unwrap_or("/").to_string();
+        let url = match Url::parse(&format!("{}://{}{}", scheme, domain, path)) {
+            Ok(u) => u,
+            Err(e) => return Err(format!("invalid cookie domain {}: {}", domain, e)),
+        };
+        match self.cookies.write().await.insert_raw(&cookie, &url) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("failed to set cookie {}: {}", cookie.name(), e)),
+        }
+    }
 
-        // Send raw request object to parent if we're tracking statistics.
-        if !self.config.no_stats {
-            self.send_to_parent(&raw_request);
+    /// Deletes the named cookie for the specified domain from the user's cookie jar,
+    /// on all paths.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(logout);
+    ///
+    ///     async fn logout(user: &GooseUser) {
+    ///       user.delete_cookie("example.com", "session").await;
+    ///     }
+    /// ```
+    pub async fn delete_cookie(&self, domain: &str, name: &str) {
+        let mut cookies = self.cookies.write().await;
+        let paths: Vec<String> = cookies
+            .iter_any()
+            .filter(|c| c.domain.as_cow().as_deref() == Some(domain) && c.name() == name)
+            .map(|c| String::from(&c.path))
+            .collect();
+        for path in paths {
+            cookies.remove(domain, &path, name);
         }
+    }
 
-        GooseResponse::new(raw_request, response)
+    /// Deletes all cookies from the user's cookie jar, for example to simulate a logout.
+    pub async fn clear_cookies(&self) {
+        self.cookies.write().await.clear();
     }
 
     fn send_to_parent(&self, raw_request: &GooseRawRequest) {
@@ -1318,13 +3238,21 @@ impl GooseUser {
     }
 
     /// If `request_name` is set, unwrap and use this. Otherwise, if `task_request_name`
-    /// is set, use this. Otherwise, use path.
+    /// is set, use this. Otherwise, use path, normalized by the first matching
+    /// `--request-name-rule`.
     fn get_request_name(&self, path: &str, request_name: Option<&str>) -> String {
         match request_name {
             Some(rn) => rn.to_string(),
             None => match &self.task_request_name {
                 Some(trn) => trn.to_string(),
-                None => path.to_string(),
+                None => {
+                    for (regex, template) in self.request_name_rules.iter() {
+                        if regex.is_match(path) {
+                            return regex.replace(path, template.as_str()).to_string();
+                        }
+                    }
+                    path.to_string()
+                }
             },
         }
     }
@@ -1362,6 +3290,8 @@ impl GooseUser {
             request.success = true;
             request.update = true;
             self.send_to_parent(&request);
+            // The update removes the error from statistics.
+            request.set_error("", "");
         }
     }
 
@@ -1409,6 +3339,9 @@ impl GooseUser {
         if request.success {
             request.success = false;
             request.update = true;
+            if request.error_kind.is_empty() {
+                request.set_error("failure", "marked as failure");
+            }
             self.send_to_parent(&request);
         }
     }
@@ -1483,7 +3416,8 @@ impl GooseUser {
         headers: Option<&header::HeaderMap>,
         body: Option<String>,
     ) {
-        if !self.config.debug_log_file.is_empty() {
+        // The --validate mode reports debug details of failing tasks.
+        if !self.config.debug_log_file.is_empty() || self.config.validate {
             // Logger is not defined when running test_start_task, test_stop_task,
             // and during testing.
             if let Some(logger) = self.logger.clone() {
@@ -1500,21 +3434,22 @@ impl GooseUser {
 
     /// Manually build a Reqwest client.
     ///
-    /// By default, Goose configures two options when building a Reqwest client. The first
-    /// configures Goose to report itself as the user agent requesting web pages (ie
-    /// `goose/0.8.2`). The second option configures Reqwest to store cookies, which is
-    /// generally necessary if you aim to simulate logged in users.
+    /// By default, Goose configures Reqwest to report itself as the user agent requesting
+    /// web pages (ie `goose/0.8.2`). Reqwest's own cookie store and redirect policy are
+    /// disabled, as Goose stores cookies in the user's cookie jar and follows redirects
+    /// itself, which is generally necessary if you aim to simulate logged in users.
     ///
     /// # Default configuration:
     ///
     /// ```rust
-    /// use reqwest::Client;
+    /// use reqwest::{redirect, Client};
     ///
     /// static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
     ///
     /// let builder = Client::builder()
     ///   .user_agent(APP_USER_AGENT)
-    ///   .cookie_store(true);
+    ///   .cookie_store(false)
+    ///   .redirect(redirect::Policy::none());
     /// ```
     ///
     /// Alternatively, you can use this function to manually build a Reqwest client with custom
@@ -1531,15 +3466,27 @@ impl GooseUser {
     ///    generating a large load test with many users, each will need to manually build their
     ///    own client (typically you'd do this in a Task that is registered with `set_on_start()`
     ///    in each Task Set requiring a custom client;
-    ///  - Manually building a client will completely replace the automatically built client
-    ///    with a brand new one, so any configuration, cookies or headers set in the previously
-    ///    built client will be gone;
-    ///  - You must include all desired configuration, as you are completely replacing Goose
-    ///    defaults. For example, if you want Goose clients to store cookies, you will have to
-    ///    include `.cookie_store(true)`.
+    ///  - Manually building a client will replace the automatically built client with a
+    ///    brand new one, so any configuration or headers set in the previously built client
+    ///    will be gone. Cookies are stored in the user's cookie jar and are kept;
+    ///  - Client options set on the command line, such as `--no-keepalive`, `--timeout`,
+    ///    `--user-agent`, `--header` or `--gzip`, are applied on top of the manually built
+    ///    client, so they can still be changed at run time. Options that aren't set on the
+    ///    command line keep the value set by the builder;
+    ///  - The user's `--source-addresses` source address is kept;
+    ///  - Requests that are already in flight when the client is replaced finish using the
+    ///    previously built client, and with `--connection-pool shared` replacing the client
+    ///    affects all users;
+    ///  - Goose still follows up to `--max-redirects` redirects;
+    ///  - Goose disables the client's own cookie store and redirect policy, as it stores
+    ///    cookies and follows redirects itself, and logs a warning the first time a client is
+    ///    built with `set_client_builder`;
+    ///  - Goose decodes gzip and deflate compressed responses itself so it can count the
+    ///    bytes both on the wire and after decoding, so the builder's own `gzip` option
+    ///    should not be enabled.
     ///
     /// In the following example, the Goose client is configured with a different user agent,
-    /// sets a default header on every request, and stores cookies.
+    /// and sets a default header on every request.
     ///
     /// # Example
     /// ```rust
@@ -1556,15 +3503,19 @@ impl GooseUser {
     ///
     ///   let builder = Client::builder()
     ///     .default_headers(headers)
-    ///     .user_agent("custom user agent")
-    ///     .cookie_store(true);
+    ///     .user_agent("custom user agent");
     ///
     ///   user.set_client_builder(builder);
     /// }
     /// ```
     pub async fn set_client_builder(&self, builder: ClientBuilder) {
-        match builder.build() {
-            Ok(c) => *self.client.lock().await = c,
+        // The builder can't be inspected, so warn once whether or not it sets them.
+        static POLICIES_WARNING: Once = Once::new();
+        POLICIES_WARNING.call_once(|| {
+            warn!("set_client_builder: any cookie store and redirect policy set on the client are replaced, Goose stores cookies in each user's cookie jar and follows up to --max-redirects redirects")
+        });
+        match configure_client(builder, &self.config, self.source_address).and_then(|b| b.build()) {
+            Ok(c) => *self.client.write().await = c,
             Err(e) => {
                 error!("failed to build web client: {}", e);
                 std::process::exit(1);
@@ -1628,6 +3579,90 @@ impl GooseUser {
             }
         }
     }
+
+    /// Change the base_url of a named host, set with `--named-host name=host`. Works the
+    /// same as `set_base_url`, but only affects requests made with `get_on`,
+    /// `post_on`, `head_on`, `delete_on` or `build_url_on` for this named host.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     let mut task = task!(switch_api);
+    ///
+    ///     async fn switch_api(user: &GooseUser) {
+    ///       user.set_named_base_url("api", "http://api2.example.com/");
+    ///       let _response = user.get_on("api", "/v1/items");
+    ///     }
+    /// ```
+    pub async fn set_named_base_url(&self, name: &str, host: &str) {
+        match Url::parse(host) {
+            Ok(url) => {
+                self.named_hosts.write().await.insert(name.to_string(), url);
+            }
+            Err(e) => {
+                error!("failed to set_named_base_url({}, {}): {}", name, host, e);
+            }
+        }
+    }
+}
+
+/// Split a `--named-host` value into the name and the host, so `api=http://10.1.1.1/`
+/// is the named host `api` at `http://10.1.1.1/`.
+pub fn parse_named_host(named_host: &str) -> Result<(&str, &str), String> {
+    let index = match named_host.find('=') {
+        Some(i) => i,
+        None => return Err(format!("expected name=host, found '{}'", named_host)),
+    };
+    let name = &named_host[..index];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "names can only contain letters, digits, '-' and '_', found '{}'",
+            name
+        ));
+    }
+    Ok((name, &named_host[index + 1..]))
+}
+
+/// Split a `--request-name-rule` into the regex matching request paths and the template
+/// replacing the match, for example `^/node/\d+=>/node/{nid}`. The template can refer
+/// to capture groups, as `$1` or `${name}`.
+pub fn parse_request_name_rule(rule: &str) -> Result<(Regex, String), String> {
+    let index = match rule.find("=>") {
+        Some(i) => i,
+        None => return Err(format!("expected regex=>template, found '{}'", rule)),
+    };
+    match Regex::new(rule[..index].trim()) {
+        Ok(regex) => Ok((regex, rule[index + 2..].trim().to_string())),
+        Err(e) => Err(format!("invalid regex in '{}': {}", rule, e)),
+    }
+}
+
+/// Compile the `--request-name-rule` rules, once per load test as they're shared by
+/// all users.
+pub(crate) fn get_request_name_rules(rules: &[String]) -> Result<Vec<(Regex, String)>, String> {
+    rules
+        .iter()
+        .map(|rule| parse_request_name_rule(rule))
+        .collect()
+}
+
+/// Build the named hosts set with `--named-host name=host`. Host validation happens in
+/// main() at startup.
+fn get_named_hosts(configuration: &GooseConfiguration) -> HashMap<String, Url> {
+    let mut named_hosts = HashMap::new();
+    for named_host in &configuration.named_host {
+        if let Ok((name, host)) = parse_named_host(named_host) {
+            if let Ok(url) = Url::parse(host) {
+                named_hosts.insert(name.to_string(), url);
+            }
+        }
+    }
+    named_hosts
 }
 
 /// A helper to determine which host should be prepended to relative load test
@@ -1671,6 +3706,8 @@ pub struct GooseTask {
     pub on_start: bool,
     /// A flag indicating that this task runs when the user stops.
     pub on_stop: bool,
+    /// Tags selecting this task with `--tags` and `--exclude-tags`.
+    pub tags: Vec<String>,
     /// A required function that is executed each time this task runs.
     pub function: for<'r> fn(&'r GooseUser) -> Pin<Box<dyn Future<Output = ()> + Send + 'r>>,
 }
@@ -1686,6 +3723,7 @@ impl GooseTask {
             sequence: 0,
             on_start: false,
             on_stop: false,
+            tags: Vec::new(),
             function,
         }
     }
@@ -1765,6 +3803,27 @@ impl GooseTask {
         self
     }
 
+    /// Add a tag to the task, which can be called more than once to add multiple tags.
+    /// With `--tags`, only tasks with one of the tags, or in a task set with one of the
+    /// tags, run. On start and on stop tasks run unless excluded with `--exclude-tags`,
+    /// so users still log in before running the selected tasks.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::prelude::*;
+    ///
+    ///     task!(task_function).set_tag("checkout");
+    ///
+    ///     async fn task_function(user: &GooseUser) {
+    ///       let _response = user.get("/checkout");
+    ///     }
+    /// ```
+    pub fn set_tag(mut self, tag: &str) -> Self {
+        trace!("{} [{}] set_tag: {}", self.name, self.tasks_index, tag);
+        self.tags.push(tag.to_string());
+        self
+    }
+
     /// Sets a weight on an individual task. The larger the value of weight, the more often it will be run
     /// in the TaskSet. For example, if one task has a weight of 3 and another task has a weight of 1, the
     /// first task will run 3 times as often.
@@ -1873,6 +3932,7 @@ impl Hash for GooseTask {
         self.sequence.hash(state);
         self.on_start.hash(state);
         self.on_stop.hash(state);
+        self.tags.hash(state);
     }
 }
 
@@ -1880,7 +3940,7 @@ impl Hash for GooseTask {
 mod tests {
     use super::*;
 
-    use httpmock::Method::{GET, POST};
+    use httpmock::Method::{GET, POST, PUT};
     use httpmock::{mock, with_mock_server};
 
     async fn setup_user() -> GooseUser {
@@ -1889,6 +3949,34 @@ mod tests {
         GooseUser::single(base_url, &configuration)
     }
 
+    /// Starts a server that answers every request with an empty 200 response after
+    /// the delay, each connection in its own thread, and returns its url.
+    fn slow_server(delay: Duration) -> String {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                        match stream.read(&mut buffer) {
+                            Ok(0) | Err(_) => return,
+                            Ok(length) => request.extend_from_slice(&buffer[..length]),
+                        }
+                    }
+                    std::thread::sleep(delay);
+                    let _ = stream.write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    );
+                });
+            }
+        });
+        url
+    }
+
     #[test]
     fn goose_task_set() {
         // Simplistic test task functions.
@@ -1984,6 +4072,38 @@ mod tests {
         task_set = task_set.set_wait_time(3, 9);
         assert_eq!(task_set.min_wait, 3);
         assert_eq!(task_set.max_wait, 9);
+
+        // HTTP version only affects the HTTP version field.
+        assert_eq!(task_set.http_version, None);
+        task_set = task_set.set_http_version(GooseHttpVersion::Http1);
+        assert_eq!(task_set.http_version, Some(GooseHttpVersion::Http1));
+        assert_eq!(task_set.host, Some("https://bar.example.com/".to_string()));
+        assert_eq!(task_set.weight, 5);
+
+        // Proxy only affects the proxy field.
+        assert_eq!(task_set.proxy, None);
+        task_set = task_set.set_proxy("http://127.0.0.1:3128");
+        assert_eq!(task_set.proxy, Some("http://127.0.0.1:3128".to_string()));
+        assert_eq!(task_set.http_version, Some(GooseHttpVersion::Http1));
+        assert_eq!(task_set.weight, 5);
+
+        // HTTP versions round trip through their string representation.
+        for http_version in &[
+            GooseHttpVersion::Auto,
+            GooseHttpVersion::Http1,
+            GooseHttpVersion::Http2,
+        ] {
+            let parsed = GooseHttpVersion::from_str(&http_version.to_string()).unwrap();
+            assert_eq!(&parsed, http_version);
+        }
+        assert!(GooseHttpVersion::from_str("3").is_err());
+    }
+
+    #[test]
+    fn goose_method_from_str() {
+        assert_eq!(GooseMethod::from_str("GET"), Ok(GooseMethod::GET));
+        assert_eq!(GooseMethod::from_str("patch"), Ok(GooseMethod::PATCH));
+        assert!(GooseMethod::from_str("OPTIONS").is_err());
     }
 
     #[test]
@@ -2064,6 +4184,40 @@ mod tests {
         assert_eq!(task.sequence, 8);
     }
 
+    #[test]
+    fn decode_response_body() {
+        use flate2::write::{GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let body = "foo".repeat(100);
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(body.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert!(gzip.len() < body.len());
+        assert_eq!(
+            decode_body("gzip", &gzip).unwrap().unwrap(),
+            body.as_bytes()
+        );
+        assert_eq!(
+            decode_body("x-gzip", &gzip).unwrap().unwrap(),
+            body.as_bytes()
+        );
+
+        let mut deflate = ZlibEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(body.as_bytes()).unwrap();
+        let deflate = deflate.finish().unwrap();
+        assert_eq!(
+            decode_body("deflate", &deflate).unwrap().unwrap(),
+            body.as_bytes()
+        );
+
+        // Unsupported encodings are left as is.
+        assert!(decode_body("br", &gzip).is_none());
+        // Invalid bodies fail to decode.
+        assert!(decode_body("gzip", body.as_bytes()).unwrap().is_err());
+    }
+
     #[test]
     fn goose_raw_request() {
         const PATH: &str = "http://127.0.0.1/";
@@ -2075,6 +4229,9 @@ mod tests {
         assert_eq!(raw_request.status_code, 0);
         assert_eq!(raw_request.success, true);
         assert_eq!(raw_request.update, false);
+        assert_eq!(raw_request.request_bytes, 0);
+        assert_eq!(raw_request.response_bytes, 0);
+        assert_eq!(raw_request.response_decompressed_bytes, 0);
 
         let response_time = 123;
         raw_request.set_response_time(response_time);
@@ -2301,6 +4458,180 @@ mod tests {
         assert_eq!(request.max_response_time, 987654321);
         assert_eq!(request.total_response_time, 987657045);
         assert_eq!(request.response_time_counter, 8);
+
+        // Tracking bytes only affects byte counters.
+        assert_eq!(request.request_bytes, 0);
+        assert_eq!(request.response_bytes, 0);
+        assert_eq!(request.response_decompressed_bytes, 0);
+        request.set_bytes(10, 100, 300);
+        assert_eq!(request.request_bytes, 10);
+        assert_eq!(request.response_bytes, 100);
+        assert_eq!(request.response_decompressed_bytes, 300);
+        // Byte counters accumulate.
+        request.set_bytes(0, 50, 50);
+        assert_eq!(request.request_bytes, 10);
+        assert_eq!(request.response_bytes, 150);
+        assert_eq!(request.response_decompressed_bytes, 350);
+        assert_eq!(request.response_time_counter, 8);
+
+        // Tracking connections only affects connection counters.
+        assert_eq!(request.connections_new, 0);
+        assert_eq!(request.connections_reused, 0);
+        request.set_connection(false);
+        assert_eq!(request.connections_new, 1);
+        assert_eq!(request.connections_reused, 0);
+        request.set_connection(true);
+        request.set_connection(true);
+        assert_eq!(request.connections_new, 1);
+        assert_eq!(request.connections_reused, 2);
+        assert_eq!(request.response_time_counter, 8);
+
+        // Tracking HTTP versions only affects HTTP version counters.
+        assert_eq!(request.http_version_counts.len(), 0);
+        request.set_http_version("HTTP/1.1");
+        request.set_http_version("HTTP/2.0");
+        request.set_http_version("HTTP/2.0");
+        // Requests without a response have no HTTP version.
+        request.set_http_version("");
+        assert_eq!(request.http_version_counts.len(), 2);
+        assert_eq!(request.http_version_counts["HTTP/1.1"], 1);
+        assert_eq!(request.http_version_counts["HTTP/2.0"], 2);
+        assert_eq!(request.response_time_counter, 8);
+
+        // Tracking source addresses only affects source address counters.
+        assert_eq!(request.source_address_counts.len(), 0);
+        request.set_source_address("127.0.0.1");
+        request.set_source_address("127.0.0.2");
+        request.set_source_address("127.0.0.1");
+        // Requests not bound to a source address aren't counted.
+        request.set_source_address("");
+        assert_eq!(request.source_address_counts.len(), 2);
+        assert_eq!(request.source_address_counts["127.0.0.1"], 2);
+        assert_eq!(request.source_address_counts["127.0.0.2"], 1);
+        assert_eq!(request.response_time_counter, 8);
+        assert_eq!(request.status_code_counts.len(), 4);
+
+        // Tracking errors only affects error counters.
+        assert_eq!(request.error_counts.len(), 0);
+        request.set_error("timeout");
+        request.set_error("status 500");
+        request.set_error("status 500");
+        // Requests that didn't fail have no error kind.
+        request.set_error("");
+        assert_eq!(request.error_counts.len(), 2);
+        assert_eq!(request.error_counts["timeout"], 1);
+        assert_eq!(request.error_counts["status 500"], 2);
+        // Requests marked as a success are no longer counted as errors.
+        request.unset_error("timeout");
+        request.unset_error("status 500");
+        request.unset_error("connect");
+        assert_eq!(request.error_counts.len(), 1);
+        assert_eq!(request.error_counts["status 500"], 1);
+        assert_eq!(request.response_time_counter, 8);
+    }
+
+    #[test]
+    fn source_addresses() {
+        let mut configuration = GooseConfiguration::default();
+        let base_url = get_base_url(Some("http://example.com/".to_string()), None, None);
+        let user = GooseUser::new(0, base_url.clone(), 0, 0, &configuration, 0);
+        assert_eq!(user.source_address, None);
+
+        // Users are bound to the source addresses round-robin by their index.
+        configuration.source_addresses = "127.0.0.1, 127.0.0.2".to_string();
+        let users: Vec<GooseUser> = [2, 0, 1]
+            .iter()
+            .map(|index| {
+                let mut user = GooseUser::new(0, base_url.clone(), 0, 0, &configuration, 0);
+                user.weighted_users_index = *index;
+                user.init_source_address();
+                user
+            })
+            .collect();
+        let first: IpAddr = "127.0.0.1".parse().unwrap();
+        let second: IpAddr = "127.0.0.2".parse().unwrap();
+        assert_eq!(users[0].source_address, Some(first));
+        assert_eq!(users[1].source_address, Some(first));
+        assert_eq!(users[2].source_address, Some(second));
+    }
+
+    #[tokio::test]
+    #[with_mock_server]
+    async fn configure_client_options() {
+        let builder = || {
+            let mut headers = header::HeaderMap::new();
+            headers.insert("x-custom", header::HeaderValue::from_static("custom"));
+            Client::builder()
+                .user_agent("custom agent")
+                .default_headers(headers)
+                .timeout(Duration::from_millis(500))
+        };
+        let slow_url = slow_server(Duration::from_secs(1));
+
+        // Options that aren't set on the command line keep the builder's values.
+        let user = setup_user().await;
+        user.set_client_builder(builder()).await;
+        let mock_index = mock(GET, "/")
+            .expect_header("User-Agent", "custom agent")
+            .expect_header("x-custom", "custom")
+            .return_status(200)
+            .create();
+        let response = user.get("/").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(mock_index.times_called(), 1);
+        let response = user.get(&slow_url).await;
+        assert!(!response.request.success);
+        assert_eq!(response.request.error_kind, "timeout");
+
+        // Options set on the command line are applied on top of the builder.
+        let configuration = GooseConfiguration {
+            user_agent: "cli agent".to_string(),
+            header: vec!["X-Custom: cli".to_string(), "X-Other: 1".to_string()],
+            timeout: Some(5),
+            ..Default::default()
+        };
+        let base_url = get_base_url(Some("http://127.0.0.1:5000".to_string()), None, None);
+        let user = GooseUser::single(base_url, &configuration);
+        user.set_client_builder(builder()).await;
+        let mock_cli = mock(GET, "/cli")
+            .expect_header("User-Agent", "cli agent")
+            .expect_header("x-custom", "cli")
+            .expect_header("x-other", "1")
+            .return_status(200)
+            .create();
+        let response = user.get("/cli").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(mock_cli.times_called(), 1);
+        let response = user.get(&slow_url).await;
+        assert_eq!(response.request.status_code, 200);
+
+        // A timeout set on the command line fires.
+        let configuration = GooseConfiguration {
+            timeout: Some(1),
+            ..Default::default()
+        };
+        let user = GooseUser::single(
+            get_base_url(Some(slow_server(Duration::from_secs(3))), None, None),
+            &configuration,
+        );
+        let response = user.get("/").await;
+        assert!(!response.request.success);
+        assert_eq!(response.request.error_kind, "timeout");
+    }
+
+    #[test]
+    fn shared_client() {
+        let configuration = GooseConfiguration::default();
+        let base_url = get_base_url(Some("http://example.com/".to_string()), None, None);
+        let mut users = vec![
+            GooseUser::new(0, base_url.clone(), 0, 0, &configuration, 0),
+            GooseUser::new(0, base_url, 0, 0, &configuration, 0),
+        ];
+        share_client(&mut users);
+        // Users share the client and connection pool, but not their cookies.
+        assert!(Arc::ptr_eq(&users[0].client, &users[1].client));
+        assert!(Arc::ptr_eq(&users[0].connections, &users[1].connections));
+        assert!(!Arc::ptr_eq(&users[0].cookies, &users[1].cookies));
     }
 
     #[tokio::test]
@@ -2416,6 +4747,327 @@ mod tests {
         assert_eq!(built_request.timeout(), None);
     }
 
+    #[test]
+    fn auth_state() {
+        // Basic and bearer authentication never request a token.
+        let state = GooseAuthState::new(GooseAuth::basic("user", "pass"));
+        assert!(!state.needs_token());
+        assert_eq!(state.token_request(), None);
+        assert_eq!(state.header(), Some("Basic dXNlcjpwYXNz".to_string()));
+        let state = GooseAuthState::new(GooseAuth::bearer("abc"));
+        assert!(!state.needs_token());
+        assert_eq!(state.header(), Some("Bearer abc".to_string()));
+
+        // OAuth2 authentication requests a token first.
+        let auth = GooseAuth::client_credentials("/token", "client", "secret").set_scope("read");
+        let mut state = GooseAuthState::new(auth);
+        assert!(state.needs_token());
+        assert_eq!(state.header(), None);
+        let (token_url, form) = state.token_request().unwrap();
+        assert_eq!(token_url, "/token");
+        assert_eq!(
+            form,
+            vec![
+                ("grant_type", "client_credentials".to_string()),
+                ("client_id", "client".to_string()),
+                ("client_secret", "secret".to_string()),
+                ("scope", "read".to_string()),
+            ]
+        );
+
+        // A token that doesn't expire soon is used.
+        state.set_token(Some(GooseAuthToken {
+            access_token: "abc".to_string(),
+            expires_in: Some(3600),
+            refresh_token: Some("refresh".to_string()),
+        }));
+        assert!(!state.needs_token());
+        assert_eq!(state.header(), Some("Bearer abc".to_string()));
+
+        // A token that is about to expire is refreshed with the refresh token.
+        state.set_token(Some(GooseAuthToken {
+            access_token: "def".to_string(),
+            expires_in: Some(AUTH_REFRESH_MARGIN - 1),
+            refresh_token: None,
+        }));
+        assert!(state.needs_token());
+        let (_, form) = state.token_request().unwrap();
+        assert_eq!(
+            form,
+            vec![
+                ("client_id", "client".to_string()),
+                ("client_secret", "secret".to_string()),
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", "refresh".to_string()),
+            ]
+        );
+
+        // A failed token request forgets the token, and backs off before retrying.
+        state.set_token(None);
+        assert!(!state.needs_token());
+        assert_eq!(state.header(), None);
+        state.retry_after = Some(Instant::now());
+        assert!(state.needs_token());
+        // The wait doubles after each consecutive failure, up to a maximum.
+        state.set_token(None);
+        let retry_in = state.retry_after.unwrap() - Instant::now();
+        assert!(retry_in > Duration::from_secs(1) && retry_in <= Duration::from_secs(2));
+        state.failures = 10;
+        state.set_token(None);
+        let retry_in = state.retry_after.unwrap() - Instant::now();
+        assert!(retry_in > Duration::from_secs(AUTH_RETRY_MAX - 1));
+        assert!(retry_in <= Duration::from_secs(AUTH_RETRY_MAX));
+        // A new token resets the backoff.
+        state.set_token(Some(GooseAuthToken {
+            access_token: "ghi".to_string(),
+            expires_in: None,
+            refresh_token: None,
+        }));
+        assert_eq!(state.failures, 0);
+        assert!(!state.needs_token());
+    }
+
+    #[tokio::test]
+    #[with_mock_server]
+    async fn auth() {
+        let mut user = setup_user().await;
+
+        // The token is requested once, and added to every request.
+        let mock_token = mock(POST, "/token")
+            .return_status(200)
+            .return_body(r#"{"access_token": "abc", "expires_in": 3600}"#)
+            .create();
+        let mock_api = mock(GET, "/api")
+            .expect_header("Authorization", "Bearer abc")
+            .return_status(200)
+            .create();
+        user.init_auth(&Some(GooseAuth::client_credentials(
+            "/token", "client", "secret",
+        )));
+        let response = user.get("/api").await;
+        assert_eq!(response.request.status_code, 200);
+        let response = user.get("/api").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(mock_token.times_called(), 1);
+        assert_eq!(mock_api.times_called(), 2);
+
+        // Replacing the provider replaces the header.
+        let mock_basic = mock(GET, "/basic")
+            .expect_header("Authorization", "Basic dXNlcjpwYXNz")
+            .return_status(200)
+            .create();
+        user.set_auth(GooseAuth::basic("user", "pass")).await;
+        let response = user.get("/basic").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(mock_basic.times_called(), 1);
+    }
+
+    #[test]
+    fn header_parsing() {
+        let (name, value) = parse_header("X-Custom-Header: custom value").unwrap();
+        assert_eq!(name, "x-custom-header");
+        assert_eq!(value, "custom value");
+        let (name, value) = parse_header("Accept:text/html").unwrap();
+        assert_eq!(name, "accept");
+        assert_eq!(value, "text/html");
+        assert!(parse_header("X-Custom-Header").is_err());
+        assert!(parse_header("X Custom Header: value").is_err());
+    }
+
+    #[tokio::test]
+    #[with_mock_server]
+    async fn client_options() {
+        let configuration = GooseConfiguration {
+            user_agent: "custom agent".to_string(),
+            header: vec![
+                "X-Custom-Header: custom value".to_string(),
+                "Accept: text/html".to_string(),
+            ],
+            timeout: Some(5),
+            connect_timeout: Some(1),
+            max_redirects: Some(0),
+            ..Default::default()
+        };
+        let base_url = get_base_url(Some("http://127.0.0.1:5000".to_string()), None, None);
+        let user = GooseUser::single(base_url, &configuration);
+
+        // The user agent and default headers are sent with every request.
+        let mock_index = mock(GET, "/")
+            .expect_header("User-Agent", "custom agent")
+            .expect_header("X-Custom-Header", "custom value")
+            .expect_header("Accept", "text/html")
+            .return_status(200)
+            .create();
+        let response = user.get("/").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(mock_index.times_called(), 1);
+
+        // Redirects aren't followed with --max-redirects 0.
+        let mock_redirect = mock(GET, "/redirect")
+            .return_status(302)
+            .return_header("Location", "/")
+            .create();
+        let response = user.get("/redirect").await;
+        assert_eq!(response.request.status_code, 302);
+        assert!(!response.request.redirected);
+        assert_eq!(mock_redirect.times_called(), 1);
+        assert_eq!(mock_index.times_called(), 1);
+    }
+
+    #[test]
+    fn request_name_rules() {
+        let configuration = GooseConfiguration {
+            request_name_rule: vec![
+                r"^/node/\d+ => /node/{nid}".to_string(),
+                r"^/user/(?P<page>[a-z]+)/\d+$=>/user/$page/{uid}".to_string(),
+                r"^/node/.*=>/node/unreachable".to_string(),
+            ],
+            ..Default::default()
+        };
+        let base_url = get_base_url(Some("http://example.com/".to_string()), None, None);
+        let mut user = GooseUser::single(base_url, &configuration);
+        user.request_name_rules =
+            Arc::new(get_request_name_rules(&configuration.request_name_rule).unwrap());
+
+        // The first matching rule normalizes the path.
+        assert_eq!(user.get_request_name("/node/123", None), "/node/{nid}");
+        assert_eq!(
+            user.get_request_name("/node/123/edit", None),
+            "/node/{nid}/edit"
+        );
+        assert_eq!(
+            user.get_request_name("/user/edit/42", None),
+            "/user/edit/{uid}"
+        );
+        assert_eq!(user.get_request_name("/about", None), "/about");
+        // Named requests aren't normalized.
+        assert_eq!(user.get_request_name("/node/123", Some("node")), "node");
+
+        assert!(parse_request_name_rule("/node/{nid}").is_err());
+        assert!(parse_request_name_rule("/node/(\\d+=>/node").is_err());
+        assert!(get_request_name_rules(&["/node/{nid}".to_string()]).is_err());
+    }
+
+    #[test]
+    fn named_host_parsing() {
+        assert_eq!(
+            parse_named_host("api=http://api.example.com/"),
+            Ok(("api", "http://api.example.com/"))
+        );
+        assert_eq!(
+            parse_named_host("web_2=http://example.com/?a=b"),
+            Ok(("web_2", "http://example.com/?a=b"))
+        );
+        // Named hosts need a name.
+        assert!(parse_named_host("http://example.com/").is_err());
+        assert!(parse_named_host("http://example.com/?a=b").is_err());
+        assert!(parse_named_host("=http://example.com/").is_err());
+    }
+
+    #[tokio::test]
+    #[with_mock_server]
+    async fn named_hosts() {
+        let configuration = GooseConfiguration {
+            host: "http://example.com/".to_string(),
+            named_host: vec!["api=http://127.0.0.1:5000/api/".to_string()],
+            ..Default::default()
+        };
+        let base_url = get_base_url(Some("http://example.com/".to_string()), None, None);
+        let user = GooseUser::single(base_url, &configuration);
+
+        // Relative paths are built from the named host.
+        assert_eq!(
+            user.build_url_on("api", "v1/items").await,
+            "http://127.0.0.1:5000/api/v1/items"
+        );
+        assert_eq!(
+            user.build_url("v1/items").await,
+            "http://example.com/v1/items"
+        );
+
+        // Requests made on a named host are tracked with the host name.
+        let mock_items = mock(GET, "/api/v1/items").return_status(200).create();
+        let response = user.get_on("api", "v1/items").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(response.request.host, "api");
+        assert_eq!(mock_items.times_called(), 1);
+
+        // Requests sent on a named host are tracked with the host name, even when the
+        // named host shares its domain with another host.
+        let mock_item = mock(PUT, "/api/v1/items/1").return_status(200).create();
+        let request_builder = user.goose_put_on("api", "v1/items/1").await;
+        let response = user.goose_send_on("api", request_builder, None).await;
+        assert_eq!(response.request.host, "api");
+        assert_eq!(mock_item.times_called(), 1);
+        let response = user.get("http://127.0.0.1:5000/api/v1/items").await;
+        assert_eq!(response.request.host, "");
+        assert_eq!(mock_items.times_called(), 2);
+
+        // Changing the base_url of a named host doesn't change the default host.
+        user.set_named_base_url("api", "http://127.0.0.1:5000/v2/")
+            .await;
+        assert_eq!(
+            user.build_url_on("api", "items").await,
+            "http://127.0.0.1:5000/v2/items"
+        );
+        assert_eq!(user.build_url("items").await, "http://example.com/items");
+    }
+
+    #[tokio::test]
+    #[with_mock_server]
+    async fn cookies() {
+        let user = setup_user().await;
+
+        // Cookies set while following a redirect are stored and sent.
+        let mock_login = mock(POST, "/login")
+            .return_status(303)
+            .return_header("Location", "/home")
+            .return_header("Set-Cookie", "session=abc; Path=/")
+            .create();
+        let mock_home = mock(GET, "/home")
+            .expect_header("Cookie", "session=abc")
+            .return_status(200)
+            .create();
+        let response = user.post("/login", "name=foo").await;
+        assert_eq!(response.request.status_code, 200);
+        assert_eq!(response.request.final_url, "http://127.0.0.1:5000/home");
+        assert_eq!(mock_login.times_called(), 1);
+        assert_eq!(mock_home.times_called(), 1);
+
+        // Stored cookies can be inspected.
+        let cookies = user.get_cookies("127.0.0.1").await;
+        assert_eq!(cookies.len(), 1);
+        let session = user.get_cookie("127.0.0.1", "session").await.unwrap();
+        assert_eq!(session.value(), "abc");
+        assert_eq!(session.path(), Some("/"));
+        assert!(user.get_cookie("127.0.0.1", "other").await.is_none());
+        assert!(user.get_cookies("example.com").await.is_empty());
+
+        // Cookies can be set and deleted.
+        user.set_cookie("127.0.0.1", GooseCookie::new("consent", "yes"))
+            .await
+            .unwrap();
+        // Invalid domains are rejected.
+        assert!(user
+            .set_cookie("exa mple.com", GooseCookie::new("consent", "yes"))
+            .await
+            .is_err());
+        assert_eq!(user.get_cookies("127.0.0.1").await.len(), 2);
+        user.delete_cookie("127.0.0.1", "session").await;
+        assert!(user.get_cookie("127.0.0.1", "session").await.is_none());
+        assert_eq!(
+            user.get_cookie("127.0.0.1", "consent")
+                .await
+                .unwrap()
+                .value(),
+            "yes"
+        );
+
+        // All cookies can be cleared.
+        user.clear_cookies().await;
+        assert!(user.get_cookies("127.0.0.1").await.is_empty());
+    }
+
     #[tokio::test]
     #[with_mock_server]
     async fn manual_requests() {
@@ -2435,6 +5087,9 @@ mod tests {
         assert_eq!(response.request.success, true);
         assert_eq!(response.request.update, false);
         assert_eq!(response.request.status_code, 200);
+        // The first request opens a new connection.
+        assert!(!response.request.connection_reused_estimate);
+        assert_eq!(response.request.http_version, "HTTP/1.1");
 
         const NO_SUCH_PATH: &str = "/no/such/path";
         let mock_404 = mock(GET, NO_SUCH_PATH).return_status(404).create();
@@ -2450,6 +5105,27 @@ mod tests {
         assert_eq!(response.request.success, false);
         assert_eq!(response.request.update, false);
         assert_eq!(response.request.status_code, 404,);
+        assert_eq!(response.request.error_kind, "status 404");
+        assert_eq!(response.request.error, "404 Not Found");
+        // The connection left idle by the first request is re-used.
+        assert!(response.request.connection_reused_estimate);
+
+        // Marking the request as a success clears the error.
+        let mut request = response.request;
+        user.set_success(&mut request);
+        assert_eq!(request.error_kind, "");
+        assert_eq!(request.error, "");
+        // Marking the request as a failure records a failure.
+        user.set_failure(&mut request);
+        assert_eq!(request.error_kind, "failure");
+
+        // Transport errors are classified by kind.
+        let response = user.get("http://127.0.0.1:1/").await;
+        assert!(response.response.is_err());
+        assert_eq!(response.request.status_code, 0);
+        assert!(!response.request.success);
+        assert_eq!(response.request.error_kind, "connect");
+        assert_ne!(response.request.error, "");
 
         // Set up a mock http server endpoint.
         const COMMENT_PATH: &str = "/comment";
@@ -2462,6 +5138,11 @@ mod tests {
         // Make a POST request to the mock http server and confirm we get a 200 OK response.
         assert_eq!(mock_comment.times_called(), 0);
         let response = user.post(COMMENT_PATH, "foo").await;
+        // The request body was counted.
+        assert_eq!(response.request.request_bytes, 3);
+        // The response body was counted as it was read.
+        assert_eq!(response.request.response_bytes, 3);
+        assert_eq!(response.request.response_decompressed_bytes, 3);
         let unwrapped_response = response.response.unwrap();
         let status = unwrapped_response.status();
         assert_eq!(status, 200);
@@ -2474,4 +5155,55 @@ mod tests {
         assert_eq!(response.request.update, false);
         assert_eq!(response.request.status_code, 200);
     }
+
+    #[tokio::test]
+    async fn concurrent_requests() {
+        const DELAY: Duration = Duration::from_millis(500);
+        let configuration = GooseConfiguration::default();
+        let base_url = get_base_url(Some(slow_server(DELAY)), None, None);
+        let user = GooseUser::single(base_url, &configuration);
+
+        // A single user can have multiple requests in flight at the same time, so two
+        // slow requests complete in less time than it takes to make them one by one.
+        let started = Instant::now();
+        let (index, about) = futures::join!(user.get("/"), user.get("/about"));
+        assert!(started.elapsed() < DELAY * 2);
+        assert_eq!(index.request.status_code, 200);
+        assert_eq!(about.request.status_code, 200);
+
+        // Replacing the client doesn't prevent further concurrent requests.
+        user.set_client_builder(Client::builder().cookie_store(true))
+            .await;
+        let started = Instant::now();
+        let (index, about) = futures::join!(user.get("/"), user.get("/about"));
+        assert!(started.elapsed() < DELAY * 2);
+        assert_eq!(index.request.status_code, 200);
+        assert_eq!(about.request.status_code, 200);
+    }
+
+    #[test]
+    fn seeded_rng() {
+        use rand::Rng;
+
+        let configuration = GooseConfiguration {
+            seed: Some(42),
+            ..Default::default()
+        };
+        let base_url = get_base_url(Some("http://127.0.0.1:5000".to_string()), None, None);
+        let mut user = GooseUser::new(0, base_url, 0, 0, &configuration, 0);
+        user.weighted_users_index = 1;
+        user.init_rng();
+        let first: Vec<u32> = (0..5).map(|_| user.rng().gen()).collect();
+
+        // The same seed and user index generate the same numbers.
+        user.init_rng();
+        let second: Vec<u32> = (0..5).map(|_| user.rng().gen()).collect();
+        assert_eq!(first, second);
+
+        // Each user generates its own numbers.
+        user.weighted_users_index = 2;
+        user.init_rng();
+        let other: Vec<u32> = (0..5).map(|_| user.rng().gen()).collect();
+        assert_ne!(first, other);
+    }
 }
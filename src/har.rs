@@ -0,0 +1,772 @@
+//! Build task sets from HAR files, such as browser sessions recorded with the browser's
+//! developer tools.
+//!
+//! Each page of the HAR file becomes a task, and the tasks run in the order the pages
+//! were recorded. A task waits the recorded think time, the time between the last
+//! response of the previous page and the first request of the page, and then makes
+//! the requests recorded for the page one after another.
+//!
+//! Requests to the host of the first recorded request are made relative to the task
+//! set's host, which defaults to the recorded host and can be changed with `set_host`
+//! or `--host`. Requests to other hosts are made as recorded, unless dropped with
+//! `drop_third_party`. Recorded values can be replaced with values from feeders with
+//! `set_substitution`.
+//!
+//! # Example
+//! ```rust,no_run
+//!     use goose::prelude::*;
+//!     use goose::feeder::GooseFeeder;
+//!     use goose::har::GooseHar;
+//!
+//!     let checkout = GooseHar::new("Checkout", "checkout.har")
+//!         .set_host("https://staging.example.com")
+//!         .drop_third_party()
+//!         .set_substitution("alice@example.com", "users", "email")
+//!         .load()
+//!         .expect("failed to load checkout.har");
+//!
+//!     GooseAttack::initialize()
+//!         .register_feeder(GooseFeeder::csv("users", "users.csv"))
+//!         .register_taskset(checkout)
+//!         .execute();
+//! ```
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+use url::{Position, Url};
+
+use crate::feeder::GooseRecord;
+use crate::goose::{GooseMethod, GooseTask, GooseTaskSet, GooseUser};
+use crate::util;
+
+/// Request headers that aren't replayed: they are set by the client, or describe a
+/// recorded connection or cookie jar.
+const SKIPPED_HEADERS: [&str; 6] = [
+    "host",
+    "connection",
+    "content-length",
+    "cookie",
+    "accept-encoding",
+    "transfer-encoding",
+];
+
+lazy_static! {
+    /// Matches a HAR timestamp.
+    static ref HAR_TIME: Regex = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})(?:\.(\d+))?(?:Z|([+-])(\d{2}):?(\d{2}))$",
+    )
+    .unwrap();
+}
+
+/// The parts of a HAR file used to build a task set, see
+/// http://www.softwareishard.com/blog/har-12-spec/
+#[derive(Deserialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Deserialize)]
+struct HarLog {
+    #[serde(default)]
+    pages: Vec<HarPage>,
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Deserialize)]
+struct HarPage {
+    id: String,
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    #[serde(default)]
+    pageref: Option<String>,
+    started_date_time: String,
+    /// How many milliseconds the request took.
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: Vec<HarHeader>,
+    #[serde(default)]
+    post_data: Option<HarPostData>,
+}
+
+#[derive(Deserialize)]
+struct HarHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+/// A recorded value replaced with a column of a feeder record.
+#[derive(Clone, Debug, Hash, PartialEq)]
+struct GooseHarSubstitution {
+    value: String,
+    feeder: String,
+    column: String,
+}
+
+/// A request recorded in a HAR file.
+#[derive(Clone, Debug, Hash, PartialEq)]
+struct GooseHarRequest {
+    method: GooseMethod,
+    /// A path relative to the task set's host, or an absolute url for other hosts.
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+}
+
+/// The requests recorded for a page.
+#[derive(Clone, Debug, Hash, PartialEq)]
+struct GooseHarPage {
+    /// The page title, or the page id if the page has no title.
+    name: String,
+    /// How many milliseconds to wait before making the page's requests.
+    think_time: u64,
+    requests: Vec<GooseHarRequest>,
+}
+
+/// The pages of a task set built from a HAR file, shared by users running the task set.
+#[derive(Debug, Hash)]
+pub(crate) struct GooseHarPages {
+    /// The recorded origin that requests are relative to, such as https://example.com.
+    origin: String,
+    /// The pages, in the order of the task set's tasks.
+    pages: Vec<GooseHarPage>,
+    substitutions: Vec<GooseHarSubstitution>,
+}
+
+/// Builds a task set from a HAR file.
+#[derive(Clone, Debug)]
+pub struct GooseHar {
+    /// The name of the task set.
+    pub name: String,
+    /// The HAR file requests are loaded from.
+    pub path: String,
+    host: Option<String>,
+    drop_third_party: bool,
+    allowed_hosts: Vec<String>,
+    substitutions: Vec<GooseHarSubstitution>,
+}
+impl GooseHar {
+    /// Create a builder for a task set named `name`, loading requests from the HAR file
+    /// at `path`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let har = GooseHar::new("Checkout", "checkout.har");
+    /// ```
+    pub fn new(name: &str, path: &str) -> Self {
+        trace!("new har: {} ({})", name, path);
+        GooseHar {
+            name: name.to_string(),
+            path: path.to_string(),
+            host: None,
+            drop_third_party: false,
+            allowed_hosts: Vec::new(),
+            substitutions: Vec::new(),
+        }
+    }
+
+    /// Set the task set's host, instead of the host of the first recorded request.
+    /// Requests recorded for that host are made against this host instead. The `--host`
+    /// option still overrides the task set's host.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let har = GooseHar::new("Checkout", "checkout.har").set_host("http://127.0.0.1:8080");
+    /// ```
+    pub fn set_host(mut self, host: &str) -> Self {
+        trace!("{} set_host: {}", self.name, host);
+        self.host = Some(host.to_string());
+        self
+    }
+
+    /// Drop requests to third-party hosts, such as analytics or ads, keeping only
+    /// requests to the host of the first recorded request and hosts allowed with
+    /// `allow_host`.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let har = GooseHar::new("Checkout", "checkout.har").drop_third_party();
+    /// ```
+    pub fn drop_third_party(mut self) -> Self {
+        trace!("{} drop_third_party", self.name);
+        self.drop_third_party = true;
+        self
+    }
+
+    /// Keep requests to another host, such as a CDN, when dropping third-party hosts.
+    /// These requests are made as recorded.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let har = GooseHar::new("Checkout", "checkout.har")
+    ///         .drop_third_party()
+    ///         .allow_host("static.example.com");
+    /// ```
+    pub fn allow_host(mut self, host: &str) -> Self {
+        trace!("{} allow_host: {}", self.name, host);
+        self.allowed_hosts.push(host.to_string());
+        self
+    }
+
+    /// Replace a recorded value in urls, headers and bodies with the `column` of a record
+    /// from the feeder named `feeder`. Each user gets a new record from the feeder each
+    /// time it runs the first page, and uses it for all pages.
+    ///
+    /// # Example
+    /// ```rust
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let har = GooseHar::new("Checkout", "checkout.har")
+    ///         .set_substitution("alice@example.com", "users", "email")
+    ///         .set_substitution("secret", "users", "password");
+    /// ```
+    pub fn set_substitution(mut self, value: &str, feeder: &str, column: &str) -> Self {
+        trace!(
+            "{} set_substitution: {} => {}.{}",
+            self.name,
+            value,
+            feeder,
+            column
+        );
+        self.substitutions.push(GooseHarSubstitution {
+            value: value.to_string(),
+            feeder: feeder.to_string(),
+            column: column.to_string(),
+        });
+        self
+    }
+
+    /// Load the HAR file and build the task set, with one task for each recorded page.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::har::GooseHar;
+    ///
+    ///     let checkout = GooseHar::new("Checkout", "checkout.har")
+    ///         .load()
+    ///         .expect("failed to load checkout.har");
+    /// ```
+    pub fn load(self) -> Result<GooseTaskSet, String> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("failed to read {}: {}", self.path, e)),
+        };
+        let pages = self.parse(&contents)?;
+        info!(
+            "loaded {} requests in {} pages for task set {} from {}",
+            pages
+                .pages
+                .iter()
+                .map(|page| page.requests.len())
+                .sum::<usize>(),
+            pages.pages.len(),
+            self.name,
+            self.path
+        );
+        Ok(self.build_task_set(pages))
+    }
+
+    /// Build the task set running the pages.
+    fn build_task_set(&self, pages: GooseHarPages) -> GooseTaskSet {
+        let host = self.host.as_ref().unwrap_or(&pages.origin);
+        let mut task_set = GooseTaskSet::new(&self.name).set_host(host);
+        for (index, page) in pages.pages.iter().enumerate() {
+            let task = GooseTask::new(move |s| Box::pin(har_page(s)))
+                .set_name(&page.name)
+                .set_sequence(index + 1);
+            task_set = task_set.register_task(task);
+        }
+        task_set.har = Some(Arc::new(pages));
+        task_set
+    }
+
+    /// Group the requests recorded in a HAR file into pages.
+    fn parse(&self, contents: &str) -> Result<GooseHarPages, String> {
+        let har: Har = match serde_json::from_str(contents) {
+            Ok(h) => h,
+            Err(e) => return Err(format!("invalid har file: {}", e)),
+        };
+
+        let mut entries = Vec::new();
+        for (index, entry) in har.log.entries.into_iter().enumerate() {
+            let started = match parse_har_time(&entry.started_date_time) {
+                Some(s) => s,
+                None => {
+                    return Err(format!(
+                        "invalid startedDateTime {} in entry {}",
+                        entry.started_date_time,
+                        index + 1
+                    ))
+                }
+            };
+            let url = match Url::parse(&entry.request.url) {
+                Ok(u) if u.scheme() == "http" || u.scheme() == "https" => u,
+                // Skip data urls, websockets and other requests Goose can't make.
+                _ => continue,
+            };
+            entries.push((started, url, entry));
+        }
+        // Group requests by page, ordering pages by their first request, and then order
+        // the requests of each page by when they were made.
+        entries.sort_by_key(|(started, _, _)| *started);
+        let mut page_order: HashMap<Option<String>, usize> = HashMap::new();
+        for (_, _, entry) in &entries {
+            let next = page_order.len();
+            page_order.entry(entry.pageref.clone()).or_insert(next);
+        }
+        entries.sort_by_key(|(started, _, entry)| (page_order[&entry.pageref], *started));
+
+        let origin = match entries.first() {
+            Some((_, url, _)) => url.origin().ascii_serialization(),
+            None => return Err("no requests found".to_string()),
+        };
+        let titles: HashMap<String, String> = har
+            .log
+            .pages
+            .into_iter()
+            .filter(|page| !page.title.is_empty())
+            .map(|page| (page.id, page.title))
+            .collect();
+
+        let mut pages: Vec<GooseHarPage> = Vec::new();
+        let mut pageref = None;
+        // When the last response of the current page was received.
+        let mut page_finished = 0;
+        let mut skipped = 0;
+        for (started, url, entry) in entries {
+            let method: GooseMethod = match entry.request.method.parse() {
+                Ok(m) => m,
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let request_url = if url.origin().ascii_serialization() == origin {
+                url[Position::BeforePath..Position::AfterQuery].to_string()
+            } else if !self.drop_third_party
+                || self
+                    .allowed_hosts
+                    .iter()
+                    .any(|host| url.host_str() == Some(host))
+            {
+                url[..Position::AfterQuery].to_string()
+            } else {
+                skipped += 1;
+                continue;
+            };
+
+            let mut headers: Vec<(String, String)> = entry
+                .request
+                .headers
+                .into_iter()
+                .filter(|header| {
+                    // HTTP/2 pseudo headers such as :authority start with a colon.
+                    !header.name.starts_with(':')
+                        && !SKIPPED_HEADERS.contains(&header.name.to_lowercase().as_str())
+                })
+                .map(|header| (header.name, header.value))
+                .collect();
+            let body = match entry.request.post_data {
+                Some(post_data) => {
+                    if !post_data.mime_type.is_empty()
+                        && !headers
+                            .iter()
+                            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                    {
+                        headers.push(("content-type".to_string(), post_data.mime_type));
+                    }
+                    post_data.text
+                }
+                None => None,
+            };
+
+            // Entries with a different page reference start a new page.
+            if pages.is_empty() || entry.pageref != pageref {
+                let name = match &entry.pageref {
+                    Some(id) => titles.get(id).unwrap_or(id).to_string(),
+                    None => "".to_string(),
+                };
+                let think_time = if pages.is_empty() {
+                    0
+                } else {
+                    (started - page_finished).max(0) as u64
+                };
+                pages.push(GooseHarPage {
+                    name,
+                    think_time,
+                    requests: Vec::new(),
+                });
+                pageref = entry.pageref;
+            }
+            page_finished = page_finished.max(started + entry.time.max(0.0) as i64);
+            pages.last_mut().unwrap().requests.push(GooseHarRequest {
+                method,
+                url: request_url,
+                headers,
+                body,
+            });
+        }
+        if skipped > 0 {
+            info!(
+                "skipped {} requests in {} with unsupported methods or third-party hosts",
+                skipped, self.path
+            );
+        }
+        if pages.is_empty() {
+            return Err("no requests found".to_string());
+        }
+
+        Ok(GooseHarPages {
+            origin,
+            pages,
+            substitutions: self.substitutions.clone(),
+        })
+    }
+}
+
+/// Make the requests recorded for the page the user is running, after waiting the
+/// recorded think time.
+async fn har_page(user: &GooseUser) {
+    let har = match &user.har {
+        Some(h) => h.clone(),
+        None => return,
+    };
+    // Tasks are registered in the order of the pages.
+    let page = match har.pages.get(user.tasks_index) {
+        Some(p) => p,
+        None => return,
+    };
+    if page.think_time > 0 {
+        tokio::time::delay_for(Duration::from_millis(page.think_time)).await;
+    }
+
+    // Each run through the pages uses new feeder records.
+    if user.tasks_index == 0 {
+        user.har_records.lock().unwrap().clear();
+    }
+    let records: Vec<(&GooseHarSubstitution, GooseRecord)> = har
+        .substitutions
+        .iter()
+        .filter_map(|substitution| {
+            let record = har_record(user, &substitution.feeder)?;
+            Some((substitution, record))
+        })
+        .collect();
+    let substitute = |text: &str| {
+        let mut text = text.to_string();
+        for (substitution, record) in &records {
+            if let Some(value) = record.get(&substitution.column) {
+                text = text.replace(&substitution.value, value);
+            }
+        }
+        text
+    };
+    // Headers such as Origin and Referer refer to the recorded host.
+    let origin = user.base_url.read().await.origin().ascii_serialization();
+
+    for request in &page.requests {
+        let url = substitute(&request.url);
+        let mut request_builder = match request.method {
+            GooseMethod::DELETE => user.goose_delete(&url).await,
+            GooseMethod::GET => user.goose_get(&url).await,
+            GooseMethod::HEAD => user.goose_head(&url).await,
+            GooseMethod::PATCH => user.goose_patch(&url).await,
+            GooseMethod::POST => user.goose_post(&url).await,
+            GooseMethod::PUT => user.goose_put(&url).await,
+        };
+        for (name, value) in &request.headers {
+            let value = substitute(value).replace(&har.origin, &origin);
+            request_builder = request_builder.header(name.as_str(), value);
+        }
+        if let Some(body) = &request.body {
+            request_builder = request_builder.body(substitute(body));
+        }
+        let _response = user.goose_send(request_builder, None).await;
+    }
+}
+
+/// Returns the user's record from a feeder for the current run through the pages,
+/// getting a new record if the user doesn't have one yet.
+fn har_record(user: &GooseUser, feeder: &str) -> Option<GooseRecord> {
+    let mut records = user.har_records.lock().unwrap();
+    if let Some(record) = records.get(feeder) {
+        return Some(record.clone());
+    }
+    let record = user.feed(feeder)?;
+    records.insert(feeder.to_string(), record.clone());
+    Some(record)
+}
+
+/// Parse a HAR timestamp, such as `2020-07-14T09:41:03.512+02:00`, into milliseconds
+/// since the Unix epoch.
+fn parse_har_time(time: &str) -> Option<i64> {
+    let captures = HAR_TIME.captures(time)?;
+    let number = |index: usize| captures[index].parse::<i64>().ok();
+    let days = util::days_from_civil(number(1)?, number(2)?, number(3)?);
+    let mut seconds = days * 86_400 + number(4)? * 3_600 + number(5)? * 60 + number(6)?;
+    if let Some(sign) = captures.get(8) {
+        let offset = number(9)? * 3_600 + number(10)? * 60;
+        seconds += if sign.as_str() == "-" {
+            offset
+        } else {
+            -offset
+        };
+    }
+    // Use the first three digits of the fraction of a second.
+    let milliseconds = match captures.get(7) {
+        Some(fraction) => format!(
+            "{:0<3}",
+            &fraction.as_str()[..fraction.as_str().len().min(3)]
+        )
+        .parse::<i64>()
+        .ok()?,
+        None => 0,
+    };
+    Some(seconds * 1_000 + milliseconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAR: &str = r#"{
+      "log": {
+        "version": "1.2",
+        "pages": [
+          {"id": "page_1", "title": "Home", "startedDateTime": "2020-07-14T09:41:00.000Z"},
+          {"id": "page_2", "title": "", "startedDateTime": "2020-07-14T09:41:05.000Z"}
+        ],
+        "entries": [
+          {
+            "pageref": "page_1",
+            "startedDateTime": "2020-07-14T09:41:00.000Z",
+            "time": 120,
+            "request": {
+              "method": "GET",
+              "url": "https://www.example.com/?ref=home#top",
+              "headers": [
+                {"name": ":authority", "value": "www.example.com"},
+                {"name": "Accept", "value": "text/html"},
+                {"name": "Cookie", "value": "session=abc"}
+              ]
+            }
+          },
+          {
+            "pageref": "page_1",
+            "startedDateTime": "2020-07-14T09:41:00.200Z",
+            "time": 300,
+            "request": {"method": "GET", "url": "https://analytics.example.net/collect", "headers": []}
+          },
+          {
+            "pageref": "page_1",
+            "startedDateTime": "2020-07-14T09:41:00.150Z",
+            "time": 50,
+            "request": {"method": "GET", "url": "https://static.example.com/app.css", "headers": []}
+          },
+          {
+            "pageref": "page_2",
+            "startedDateTime": "2020-07-14T11:41:05.000+02:00",
+            "time": 80,
+            "request": {
+              "method": "POST",
+              "url": "https://www.example.com/login",
+              "headers": [{"name": "Origin", "value": "https://www.example.com"}],
+              "postData": {"mimeType": "application/x-www-form-urlencoded", "text": "user=alice&pass=secret"}
+            }
+          },
+          {
+            "pageref": "page_2",
+            "startedDateTime": "2020-07-14T09:41:05.010Z",
+            "time": 10,
+            "request": {"method": "OPTIONS", "url": "https://www.example.com/api", "headers": []}
+          },
+          {
+            "startedDateTime": "2020-07-14T09:41:06.000Z",
+            "time": 10,
+            "request": {"method": "GET", "url": "data:image/png;base64,AAAA", "headers": []}
+          }
+        ]
+      }
+    }"#;
+
+    fn request(method: GooseMethod, url: &str) -> GooseHarRequest {
+        GooseHarRequest {
+            method,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn parse_pages() {
+        let pages = GooseHar::new("Home", "home.har").parse(HAR).unwrap();
+        assert_eq!(pages.origin, "https://www.example.com");
+        assert_eq!(pages.pages.len(), 2);
+
+        // Requests are ordered by when they started, and requests to the recorded host
+        // are relative.
+        let mut home = request(GooseMethod::GET, "/?ref=home");
+        home.headers = vec![("Accept".to_string(), "text/html".to_string())];
+        assert_eq!(
+            pages.pages[0],
+            GooseHarPage {
+                name: "Home".to_string(),
+                think_time: 0,
+                requests: vec![
+                    home,
+                    request(GooseMethod::GET, "https://static.example.com/app.css"),
+                    request(GooseMethod::GET, "https://analytics.example.net/collect"),
+                ],
+            }
+        );
+
+        // Pages without a title are named by id, and wait from the last response of
+        // the previous page.
+        let mut login = request(GooseMethod::POST, "/login");
+        login.headers = vec![
+            ("Origin".to_string(), "https://www.example.com".to_string()),
+            (
+                "content-type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            ),
+        ];
+        login.body = Some("user=alice&pass=secret".to_string());
+        assert_eq!(
+            pages.pages[1],
+            GooseHarPage {
+                name: "page_2".to_string(),
+                think_time: 4_500,
+                requests: vec![login],
+            }
+        );
+    }
+
+    #[test]
+    fn interleaved_pages() {
+        let har = r#"{"log": {"entries": [
+          {"pageref": "page_1", "startedDateTime": "2020-07-14T09:41:00.000Z", "time": 100,
+           "request": {"method": "GET", "url": "https://www.example.com/"}},
+          {"pageref": "page_2", "startedDateTime": "2020-07-14T09:41:02.000Z", "time": 100,
+           "request": {"method": "GET", "url": "https://www.example.com/about"}},
+          {"pageref": "page_1", "startedDateTime": "2020-07-14T09:41:03.000Z", "time": 100,
+           "request": {"method": "POST", "url": "https://www.example.com/beacon"}},
+          {"pageref": "page_2", "startedDateTime": "2020-07-14T09:41:01.000Z", "time": 100,
+           "request": {"method": "GET", "url": "https://www.example.com/about.css"}}
+        ]}}"#;
+        let pages = GooseHar::new("Home", "home.har").parse(har).unwrap();
+        // Requests made after the next page started still belong to their own page.
+        assert_eq!(pages.pages.len(), 2);
+        assert_eq!(
+            pages.pages[0].requests,
+            vec![
+                request(GooseMethod::GET, "/"),
+                request(GooseMethod::POST, "/beacon"),
+            ]
+        );
+        assert_eq!(
+            pages.pages[1].requests,
+            vec![
+                request(GooseMethod::GET, "/about.css"),
+                request(GooseMethod::GET, "/about"),
+            ]
+        );
+    }
+
+    #[test]
+    fn third_party_hosts() {
+        let pages = GooseHar::new("Home", "home.har")
+            .drop_third_party()
+            .allow_host("static.example.com")
+            .parse(HAR)
+            .unwrap();
+        let urls: Vec<&str> = pages.pages[0]
+            .requests
+            .iter()
+            .map(|request| request.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["/?ref=home", "https://static.example.com/app.css"]
+        );
+    }
+
+    #[test]
+    fn task_set() {
+        let har = GooseHar::new("Home", "home.har");
+        let task_set = har.build_task_set(har.parse(HAR).unwrap());
+        assert_eq!(task_set.host, Some("https://www.example.com".to_string()));
+        assert_eq!(task_set.tasks.len(), 2);
+        assert_eq!(task_set.tasks[0].name, "Home");
+        assert_eq!(task_set.tasks[0].sequence, 1);
+        assert_eq!(task_set.tasks[1].name, "page_2");
+        assert_eq!(task_set.tasks[1].sequence, 2);
+
+        let har = har.set_host("http://127.0.0.1:8080");
+        let task_set = har.build_task_set(har.parse(HAR).unwrap());
+        assert_eq!(task_set.host, Some("http://127.0.0.1:8080".to_string()));
+    }
+
+    #[test]
+    fn invalid_har() {
+        let har = GooseHar::new("Home", "home.har");
+        assert!(har.parse("{}").is_err());
+        assert!(har.parse(r#"{"log": {"entries": []}}"#).is_err());
+    }
+
+    #[test]
+    fn har_time() {
+        assert_eq!(
+            parse_har_time("2020-07-14T09:41:03.512Z"),
+            Some(1_594_719_663_512)
+        );
+        assert_eq!(
+            parse_har_time("2020-07-14T11:41:03.5+02:00"),
+            Some(1_594_719_663_500)
+        );
+        assert_eq!(
+            parse_har_time("2020-07-14T09:41:03.512345Z"),
+            Some(1_594_719_663_512)
+        );
+        assert_eq!(
+            parse_har_time("2020-07-14T09:41:03Z"),
+            Some(1_594_719_663_000)
+        );
+        assert!(parse_har_time("July 14, 2020").is_none());
+    }
+}
@@ -290,33 +290,44 @@ extern crate log;
 
 extern crate structopt;
 
+mod abort;
+mod config;
+mod cookies;
+pub mod feeder;
 pub mod goose;
+pub mod har;
 pub mod logger;
 #[cfg(feature = "gaggle")]
 mod manager;
 pub mod prelude;
+mod replay;
 mod stats;
 mod user;
 mod util;
+mod validate;
 #[cfg(feature = "gaggle")]
 mod worker;
 
 use lazy_static::lazy_static;
 #[cfg(feature = "gaggle")]
 use nng::Socket;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use simplelog::*;
+use std::any::Any;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::f32;
 use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
     Arc,
 };
 use std::time;
+use structopt::clap::ArgMatches;
 use structopt::StructOpt;
 use tokio::fs::File;
 use tokio::io::BufWriter;
@@ -324,8 +335,10 @@ use tokio::prelude::*;
 use tokio::sync::mpsc;
 use url::Url;
 
+use crate::feeder::GooseFeeder;
 use crate::goose::{
-    GooseDebug, GooseRawRequest, GooseRequest, GooseTask, GooseTaskSet, GooseUser, GooseUserCommand,
+    GooseConnectionPool, GooseDebug, GooseHttpVersion, GooseRawRequest, GooseRequest, GooseTask,
+    GooseTaskSet, GooseUser, GooseUserCommand,
 };
 
 /// Constant defining how often statistics should be displayed while load test is running.
@@ -379,10 +392,36 @@ pub struct GooseAttack {
     active_users: usize,
     /// All requests statistics merged together.
     merged_requests: HashMap<String, GooseRequest>,
+    /// How many times users completed their task set, by task set index.
+    iterations: HashMap<usize, usize>,
+    /// Bytes sent and received, sampled each time running statistics are due.
+    bandwidth: Vec<stats::GooseBandwidthSample>,
+    /// Why the load test was aborted, if an abort condition was met.
+    aborted: Option<String>,
+    /// Requests replayed by users with `--replay`.
+    replay: Option<Arc<replay::GooseReplay>>,
+    /// Rules normalizing request paths into request names, compiled once and shared by users.
+    request_name_rules: Arc<Vec<(Regex, String)>>,
+    /// Whether new request names are being counted as "other", after `--max-request-names`.
+    request_names_capped: bool,
+    /// Feeders handing test data records to users, by name.
+    feeders: HashMap<String, GooseFeeder>,
+    /// Cookies loaded from `--cookies-file`, seeding each user's cookie jar.
+    cookies: Vec<(Url, goose::GooseCookie)>,
+    /// The load test's own command line options, set by `initialize_with_options()`.
+    options: Option<Arc<dyn Any + Send + Sync>>,
+    /// Options set on the command line, in the environment or in a config file, which
+    /// defaults set by the load test don't override. Unknown for configurations loaded
+    /// elsewhere, where options that differ from the built-in defaults are kept instead.
+    configured_options: Option<HashSet<String>>,
+    /// Options set by the load test with `set_default`, so defaults for options that can
+    /// be repeated are combined.
+    defaulted_options: HashSet<String>,
 }
 /// Goose's internal global state.
 impl GooseAttack {
-    /// Load configuration from command line and initialize a GooseAttack.
+    /// Load configuration from the command line, `GOOSE_*` environment variables and
+    /// `--config-file`, and initialize a GooseAttack.
     ///
     /// # Example
     /// ```rust,no_run
@@ -391,21 +430,68 @@ impl GooseAttack {
     ///     let mut goose_attack = GooseAttack::initialize();
     /// ```
     pub fn initialize() -> GooseAttack {
-        let goose_attack = GooseAttack {
-            test_start_task: None,
-            test_stop_task: None,
-            task_sets: Vec::new(),
-            task_sets_hash: 0,
-            weighted_users: Vec::new(),
-            host: None,
-            configuration: GooseConfiguration::from_args(),
-            number_of_cpus: num_cpus::get(),
-            run_time: 0,
-            users: 0,
-            active_users: 0,
-            merged_requests: HashMap::new(),
-        };
-        goose_attack.setup()
+        GooseAttack::initialize_with_matches(&GooseConfiguration::clap().get_matches())
+    }
+
+    /// Load configuration as `initialize()` does, also parsing the load test's own
+    /// command line options defined with StructOpt, which are flattened into Goose's
+    /// options and must not conflict with them. The parsed options are available from
+    /// `GooseAttack::get_options()` and `GooseUser::get_options()`. When running a
+    /// Gaggle, each worker parses its own command line options.
+    ///
+    /// The load test's options are only read from the command line: they can't be set
+    /// in `--config-file`, and aren't read from `GOOSE_*` environment variables. Add
+    /// StructOpt's `env` attribute to an option to also read it from the environment.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///     use structopt::StructOpt;
+    ///
+    ///     #[derive(StructOpt)]
+    ///     struct LoadTestOptions {
+    ///         /// Tenant to load test
+    ///         #[structopt(long, env = "TENANT", default_value = "default")]
+    ///         tenant: String,
+    ///     }
+    ///
+    ///     let goose_attack = GooseAttack::initialize_with_options::<LoadTestOptions>();
+    ///     let tenant = &goose_attack.get_options::<LoadTestOptions>().unwrap().tenant;
+    /// ```
+    pub fn initialize_with_options<T>() -> GooseAttack
+    where
+        T: StructOpt + Send + Sync + 'static,
+    {
+        let matches = GooseConfiguration::augment_options(T::clap()).get_matches();
+        let mut goose_attack = GooseAttack::initialize_with_matches(&matches);
+        goose_attack.options = Some(Arc::new(T::from_clap(&matches)));
+        goose_attack
+    }
+
+    /// Load configuration from parsed command line arguments, the environment and
+    /// `--config-file`, then initialize the logger and validate the configuration.
+    fn initialize_with_matches(matches: &ArgMatches) -> GooseAttack {
+        match GooseConfiguration::load_from_matches(matches) {
+            Ok((configuration, configured_options)) => {
+                let mut goose_attack = GooseAttack::initialize_with_config(configuration);
+                goose_attack.configured_options = Some(configured_options);
+                goose_attack.setup()
+            }
+            Err(e) => {
+                // Log the error as configured on the command line.
+                GooseAttack::initialize_with_config(GooseConfiguration::from_clap(matches))
+                    .initialize_logger();
+                error!("Invalid configuration: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// Get the load test's own command line options, parsed by
+    /// `GooseAttack::initialize_with_options()`. Returns `None` if the load test was
+    /// initialized without options, or with options of another type.
+    pub fn get_options<T: Any>(&self) -> Option<&T> {
+        self.options.as_ref().and_then(|o| o.downcast_ref::<T>())
     }
 
     /// Initialize a GooseAttack with an already loaded configuration.
@@ -414,10 +500,9 @@ impl GooseAttack {
     /// # Example
     /// ```rust,no_run
     ///     use goose::{GooseAttack, GooseConfiguration};
-    ///     use structopt::StructOpt;
     ///
-    ///     let configuration = GooseConfiguration::from_args();
-    ///     let mut goose_attack = GooseAttack::initialize_with_config(configuration);
+    ///     let configuration = GooseConfiguration::load().unwrap();
+    ///     let mut goose_attack = GooseAttack::initialize_with_config(configuration).setup();
     /// ```
     pub fn initialize_with_config(config: GooseConfiguration) -> GooseAttack {
         GooseAttack {
@@ -433,6 +518,17 @@ impl GooseAttack {
             users: 0,
             active_users: 0,
             merged_requests: HashMap::new(),
+            iterations: HashMap::new(),
+            bandwidth: vec![stats::GooseBandwidthSample::default()],
+            aborted: None,
+            replay: None,
+            request_name_rules: Arc::new(Vec::new()),
+            request_names_capped: false,
+            feeders: HashMap::new(),
+            cookies: Vec::new(),
+            options: None,
+            configured_options: None,
+            defaulted_options: HashSet::new(),
         }
     }
 
@@ -480,9 +576,17 @@ impl GooseAttack {
         info!("Writing to log file: {}", log_file.display());
     }
 
+    /// Initialize the logger and validate the configuration. This is called by
+    /// `initialize()`, and must be called after `initialize_with_config()`.
     pub fn setup(mut self) -> Self {
         self.initialize_logger();
+        self.validate_configuration();
+        self
+    }
 
+    /// Validate the configuration, which is validated again after applying a default
+    /// set with `set_default()`.
+    fn validate_configuration(&mut self) {
         // Collecting statistics is required for the following options.
         if self.configuration.no_stats {
             // Don't allow overhead of collecting statistics unless we're printing them.
@@ -508,6 +612,25 @@ impl GooseAttack {
                 error!("You must not enable --no-stats when enabling --stats-log-format.");
                 std::process::exit(1);
             }
+
+            // Abort conditions are evaluated against statistics.
+            if self.configuration.abort_fail_rate > 0
+                || self.configuration.abort_p95 > 0
+                || self.configuration.abort_transport_errors > 0
+            {
+                error!("You must not enable --no-stats when setting --abort-fail-rate, --abort-p95 or --abort-transport-errors.");
+                std::process::exit(1);
+            }
+        }
+
+        if self.configuration.abort_fail_rate > 100 {
+            error!("The --abort-fail-rate must be a percentage from 0 to 100.");
+            std::process::exit(1);
+        }
+
+        if self.configuration.abort_window < 1 {
+            error!("The --abort-window must be at least 1 second.");
+            std::process::exit(1);
         }
 
         if self.configuration.stats_log_format != "json" {
@@ -528,6 +651,66 @@ impl GooseAttack {
             }
         }
 
+        if self.configuration.list_format != "text" {
+            // List format is only used by --list.
+            if !self.configuration.list {
+                error!("You must enable --list when setting --list-format.");
+                std::process::exit(1);
+            }
+
+            let options = ["text", "json"];
+            if !options.contains(&self.configuration.list_format.as_str()) {
+                error!(
+                    "The --list-format must be set to one of: {}.",
+                    options.join(", ")
+                );
+                std::process::exit(1);
+            }
+        }
+
+        if self.configuration.validate && (self.configuration.manager || self.configuration.worker)
+        {
+            error!("You must not enable --validate when running in manager or worker mode.");
+            std::process::exit(1);
+        }
+
+        if !self.configuration.replay.is_empty() {
+            if self.configuration.manager || self.configuration.worker {
+                error!("You must not set --replay when running in manager or worker mode.");
+                std::process::exit(1);
+            }
+
+            if self.configuration.validate {
+                error!("You must not enable --validate when setting --replay.");
+                std::process::exit(1);
+            }
+
+            // Each user in the log is replayed by one user.
+            if self.configuration.users.is_some() {
+                error!("You must not set --users when setting --replay.");
+                std::process::exit(1);
+            }
+
+            // Each user stops after replaying all of its requests.
+            if self.configuration.iterations > 0 {
+                error!("You must not set --iterations when setting --replay.");
+                std::process::exit(1);
+            }
+        }
+
+        if self.configuration.replay_speed != "1" {
+            // Speed isn't relevant if not replaying.
+            if self.configuration.replay.is_empty() {
+                error!("You must set --replay when setting --replay-speed.");
+                std::process::exit(1);
+            }
+
+            if !replay::is_valid_speed(&self.configuration.replay_speed) {
+                error!("The --replay-speed must be a positive number or \"asap\".");
+                std::process::exit(1);
+            }
+        }
+
         if self.configuration.debug_log_format != "json" {
             // Log format isn't relevant if log not enabled.
             if self.configuration.debug_log_file.is_empty() {
@@ -546,52 +729,58 @@ impl GooseAttack {
             }
         }
 
-        // Configure maximum run time if specified, otherwise run until canceled.
-        if self.configuration.worker {
-            if self.configuration.run_time != "" {
-                error!("The --run-time option is only available to the manager.");
+        // Keep-alive must be enabled to configure idle connections.
+        if self.configuration.no_keepalive {
+            if self.configuration.max_idle_connections.is_some() {
+                error!("You must not enable --no-keepalive when setting --max-idle-connections.");
+                std::process::exit(1);
+            }
+            if self.configuration.idle_timeout.is_some() {
+                error!("You must not enable --no-keepalive when setting --idle-timeout.");
                 std::process::exit(1);
             }
-            self.run_time = 0;
-        } else if self.configuration.run_time != "" {
-            self.run_time = util::parse_timespan(&self.configuration.run_time);
-            info!("run_time = {}", self.run_time);
-        } else {
-            self.run_time = 0;
         }
 
-        // Configure number of user threads to launch, default to the number of CPU cores available.
-        self.users = match self.configuration.users {
-            Some(u) => {
-                if u == 0 {
-                    if self.configuration.worker {
-                        error!("At least 1 user is required.");
-                        std::process::exit(1);
-                    } else {
-                        0
-                    }
-                } else {
-                    if self.configuration.worker {
-                        error!("The --users option is only available to the manager.");
-                        std::process::exit(1);
-                    }
-                    u
-                }
+        // Timeouts of 0 would fail all requests.
+        if self.configuration.timeout == Some(0) {
+            error!("The --timeout option must be greater than 0.");
+            std::process::exit(1);
+        }
+        if self.configuration.connect_timeout == Some(0) {
+            error!("The --connect-timeout option must be greater than 0.");
+            std::process::exit(1);
+        }
+
+        // Confirm the user agent and all default headers are valid.
+        if !self.configuration.user_agent.is_empty()
+            && reqwest::header::HeaderValue::from_str(&self.configuration.user_agent).is_err()
+        {
+            error!("Invalid --user-agent '{}'.", self.configuration.user_agent);
+            std::process::exit(1);
+        }
+        for header in &self.configuration.header {
+            if let Err(e) = goose::parse_header(header) {
+                error!("Invalid --header: {}", e);
+                std::process::exit(1);
             }
-            None => {
-                let u = self.number_of_cpus;
-                if !self.configuration.manager && !self.configuration.worker {
-                    info!("concurrent users defaulted to {} (number of CPUs)", u);
+        }
+
+        // Confirm all source addresses are valid IP addresses.
+        if !self.configuration.source_addresses.is_empty() {
+            for address in self.configuration.source_addresses.split(',') {
+                if let Err(e) = address.trim().parse::<IpAddr>() {
+                    error!("Invalid --source-addresses address '{}': {}", address, e);
+                    std::process::exit(1);
                 }
-                u
             }
-        };
-
-        if !self.configuration.manager && !self.configuration.worker {
-            debug!("users = {}", self.users);
+            // Users sharing a client also share its source address.
+            if self.configuration.connection_pool == GooseConnectionPool::Shared {
+                error!(
+                    "You must not set --connection-pool shared when setting --source-addresses."
+                );
+                std::process::exit(1);
+            }
         }
-
-        self
     }
 
     /// A load test must contain one or more `GooseTaskSet`s. Each task set must
@@ -692,13 +881,318 @@ impl GooseAttack {
         self
     }
 
-    /// Allocate a vector of weighted GooseUser.
+    /// Register a feeder, handing records of test data to users with `GooseUser::feed`.
+    /// Records are loaded from the feeder's file when the load test starts.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///     use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+    ///
+    ///     GooseAttack::initialize()
+    ///         .register_feeder(GooseFeeder::csv("users", "users.csv").set_strategy(GooseFeederStrategy::Unique));
+    /// ```
+    pub fn register_feeder(mut self, feeder: GooseFeeder) -> Self {
+        trace!("register_feeder: {}", feeder.name);
+        self.feeders.insert(feeder.name.to_string(), feeder);
+        self
+    }
+
+    /// Add a default for a configuration option, used when the option is not set on
+    /// the command line, in the environment or in a config file.
+    fn add_default(mut self, key: GooseDefault, value: serde_json::Value) -> Self {
+        let name = key.option();
+        trace!("set_default: {} = {}", name, value);
+        // Workers receive their configuration from the manager.
+        if self.configuration.worker {
+            return self;
+        }
+        if self.configured_options.is_none() {
+            self.configured_options = Some(config::changed_options(&self.configuration));
+        }
+        let combine = !self.defaulted_options.insert(name.to_string());
+        match config::apply_default(&self.configuration, &name, value, combine) {
+            Ok(configuration) => {
+                let configured = match &self.configured_options {
+                    Some(c) => c.contains(&name),
+                    None => false,
+                };
+                if !configured {
+                    self.configuration = configuration;
+                    self.validate_configuration();
+                }
+            }
+            Err(e) => {
+                error!("Invalid default for {}: {}", name, e);
+                std::process::exit(1);
+            }
+        }
+        self
+    }
+
+    /// Add a rule normalizing request paths into request names, to avoid tracking
+    /// statistics for each unique path. The first rule whose regex matches a path
+    /// replaces the matching part of the path with the template, which can refer to
+    /// capture groups as `$1` or `${name}`. Rules set with `--request-name-rule` are
+    /// tried first. Requests named with `get_named` and similar are not normalized.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    ///     use goose::prelude::*;
+    ///
+    ///     GooseAttack::initialize()
+    ///         .set_request_name_rule(r"^/node/\d+", "/node/{nid}");
+    /// ```
+    pub fn set_request_name_rule(mut self, regex: &str, template: &str) -> Self {
+        trace!("set_request_name_rule: {} => {}", regex, template);
+        // Rule validation happens in main() at startup.
+        self.configuration
+            .request_name_rule
+            .push(format!("{}=>{}", regex, template));
+        self
+    }
+
+    /// Override the weights of task sets and tasks with `--taskset-weight` and
+    /// `--task-weight`.
+    fn apply_weights(&mut self) {
+        for value in &self.configuration.taskset_weight {
+            let (name, weight) = match parse_weight(value) {
+                Ok(w) => w,
+                Err(e) => {
+                    error!("Invalid --taskset-weight: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            match self.task_sets.iter_mut().find(|t| t.name == name) {
+                Some(task_set) => {
+                    info!("{} weight set to {}", name, weight);
+                    task_set.weight = weight;
+                }
+                None => {
+                    error!("Unknown task set '{}' in --taskset-weight.", name);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        for value in &self.configuration.task_weight {
+            let (name, weight) = match parse_weight(value) {
+                Ok(w) => w,
+                Err(e) => {
+                    error!("Invalid --task-weight: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            // Task set and task names can both contain dots, so match on the task set name.
+            let mut found = false;
+            for task_set in &mut self.task_sets {
+                let prefix = format!("{}.", task_set.name);
+                if !name.starts_with(&prefix) {
+                    continue;
+                }
+                let task_name = &name[prefix.len()..];
+                for task in task_set.tasks.iter_mut().filter(|t| t.name == task_name) {
+                    info!("{} weight set to {}", name, weight);
+                    task.weight = weight;
+                    found = true;
+                }
+            }
+            if !found {
+                error!("Unknown task '{}' in --task-weight.", name);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    /// The selected task sets and tasks as JSON for `--list --list-format json`,
+    /// including the order tasks run in and the load test hash.
+    fn list_json(&self) -> serde_json::Value {
+        // Weight tasks as the load test would, to include the weighted order and to
+        // calculate the same hash.
+        let mut task_sets = self.task_sets.clone();
+        set_weighted_tasks(&mut task_sets, &self.configuration);
+
+        let mut list = Vec::new();
+        for task_set in &task_sets {
+            if !is_task_set_selected(&self.configuration, task_set) {
+                continue;
+            }
+            let tasks: Vec<serde_json::Value> = task_set
+                .tasks
+                .iter()
+                .filter(|t| is_task_selected(&self.configuration, task_set, t))
+                .map(|t| {
+                    json!({
+                        "index": t.tasks_index,
+                        "name": t.name,
+                        "weight": t.weight,
+                        "sequence": t.sequence,
+                        "on_start": t.on_start,
+                        "on_stop": t.on_stop,
+                        "tags": t.tags,
+                    })
+                })
+                .collect();
+            list.push(json!({
+                "name": task_set.name,
+                "weight": task_set.weight,
+                "fixed_users": task_set.fixed_users,
+                "min_users": task_set.min_users,
+                "min_wait": task_set.min_wait,
+                "max_wait": task_set.max_wait,
+                "host": task_set.host,
+                "tags": task_set.tags,
+                "tasks": tasks,
+                "weighted_on_start_tasks": task_set.weighted_on_start_tasks,
+                "weighted_tasks": task_set.weighted_tasks,
+                "weighted_on_stop_tasks": task_set.weighted_on_stop_tasks,
+            }));
+        }
+        json!({
+            // As a string, as JSON numbers often can't represent all 64 bit integers.
+            "load_test_hash": load_test_hash(&task_sets, &self.configuration).to_string(),
+            "host": self.host,
+            "task_sets": list,
+        })
+    }
+
+    /// Display the users running each selected task set, and how often each of its
+    /// tasks runs.
+    fn print_mix(&self) {
+        let task_sets: Vec<&GooseTaskSet> = self
+            .task_sets
+            .iter()
+            .filter(|t| is_task_set_selected(&self.configuration, t))
+            .collect();
+        let total_users = self.weighted_users.len().max(1);
+        println!("Load test mix:");
+        for task_set in task_sets {
+            let share = match (task_set.fixed_users, task_set.min_users) {
+                (Some(fixed), _) => format!("fixed users: {}", fixed),
+                (None, Some(min)) => format!("weight: {}, minimum users: {}", task_set.weight, min),
+                (None, None) => format!("weight: {}", task_set.weight),
+            };
+            let users = self
+                .weighted_users
+                .iter()
+                .filter(|u| u.task_sets_index == task_set.task_sets_index)
+                .count();
+            println!(
+                " - {} ({}, {} users, {:.2}% of users)",
+                task_set.name,
+                share,
+                users,
+                users as f32 / total_users as f32 * 100.0
+            );
+            let tasks: Vec<&GooseTask> = task_set
+                .tasks
+                .iter()
+                .filter(|t| is_task_selected(&self.configuration, task_set, t))
+                .collect();
+            let tasks_total: usize = tasks
+                .iter()
+                .filter(|t| !t.on_start && !t.on_stop)
+                .map(|t| t.weight)
+                .sum();
+            for task in tasks {
+                if task.on_start || task.on_stop {
+                    let when = if task.on_start { "on start" } else { "on stop" };
+                    println!("    o {} ({})", task.name, when);
+                } else {
+                    println!(
+                        "    o {} (weight: {}, {:.2}% of tasks)",
+                        task.name,
+                        task.weight,
+                        task.weight as f32 / tasks_total as f32 * 100.0
+                    );
+                }
+            }
+        }
+        println!();
+    }
+
+    /// Allocate a state for a user running the given task set.
+    fn build_user(&self, task_set: &GooseTaskSet) -> GooseUser {
+        let base_url = goose::get_base_url(
+            self.get_configuration_host(),
+            task_set.host.clone(),
+            self.host.clone(),
+        );
+        // The `--http-version` and `--proxy` CLI options override the task set
+        // HTTP version and proxy.
+        let mut configuration = self.configuration.clone();
+        if configuration.http_version.is_none() {
+            configuration.http_version = task_set.http_version;
+        }
+        if configuration.proxy.is_empty() {
+            if let Some(proxy) = &task_set.proxy {
+                configuration.proxy = proxy.to_string();
+            }
+        }
+        let mut user = GooseUser::new(
+            task_set.task_sets_index,
+            base_url,
+            task_set.min_wait,
+            task_set.max_wait,
+            &configuration,
+            self.task_sets_hash,
+        );
+        user.init_cookies(&self.cookies);
+        user.request_name_rules = Arc::clone(&self.request_name_rules);
+        user.init_auth(&task_set.auth);
+        user.har = task_set.har.clone();
+        user
+    }
+
+    /// Allocate a vector of weighted GooseUser. Task sets with a fixed or minimum number
+    /// of users are assigned those users first, remaining users are distributed to task
+    /// sets without a fixed number of users by weight.
     fn weight_task_set_users(&mut self) -> Vec<GooseUser> {
         trace!("weight_task_set_users");
 
+        // Interleave the users required by fixed and minimum user counts.
+        let mut required_users = Vec::new();
+        for (index, task_set) in self.task_sets.iter().enumerate() {
+            if !is_task_set_selected(&self.configuration, task_set) {
+                continue;
+            }
+            if task_set.fixed_users.is_some() && task_set.min_users.is_some() {
+                error!(
+                    "{} can't have both a fixed and a minimum number of users.",
+                    task_set.name
+                );
+                std::process::exit(1);
+            }
+            if let Some(users) = task_set.fixed_users.or(task_set.min_users) {
+                required_users.push((index, users));
+            }
+        }
+        let required: usize = required_users.iter().map(|(_, users)| users).sum();
+        if required > self.users {
+            error!(
+                "At least {} users are required by the fixed and minimum users of task sets.",
+                required
+            );
+            std::process::exit(1);
+        }
+        let mut task_sets_indexes = Vec::new();
+        while task_sets_indexes.len() < required {
+            for (index, users) in &mut required_users {
+                if *users > 0 {
+                    task_sets_indexes.push(*index);
+                    *users -= 1;
+                }
+            }
+        }
+
         let mut u: usize = 0;
         let mut v: usize;
         for task_set in &self.task_sets {
+            if !is_task_set_selected(&self.configuration, task_set)
+                || task_set.fixed_users.is_some()
+            {
+                continue;
+            }
             if u == 0 {
                 u = task_set.weight;
             } else {
@@ -714,6 +1208,14 @@ impl GooseAttack {
         // Build a weighted lists of task sets (identified by index)
         let mut weighted_task_sets = Vec::new();
         for (index, task_set) in self.task_sets.iter().enumerate() {
+            if !is_task_set_selected(&self.configuration, task_set) {
+                debug!("{} is not selected to run", task_set.name);
+                continue;
+            }
+            if let Some(users) = task_set.fixed_users {
+                debug!("{} has {} fixed users", task_set.name, users);
+                continue;
+            }
             // divide by greatest common divisor so vector is as short as possible
             let weight = task_set.weight / u;
             trace!(
@@ -727,32 +1229,33 @@ impl GooseAttack {
             weighted_task_sets.append(&mut weighted_sets);
         }
 
+        // Users can only be added to task sets without a fixed number of users.
+        if weighted_task_sets.is_empty() && self.users > required {
+            warn!(
+                "all task sets have a fixed number of users, launching {} users",
+                required
+            );
+            self.users = required;
+        }
+
+        // Distribute the remaining users by weight.
+        while task_sets_indexes.len() < self.users {
+            for index in &weighted_task_sets {
+                task_sets_indexes.push(*index);
+                if task_sets_indexes.len() >= self.users {
+                    break;
+                }
+            }
+        }
+
         // Allocate a state for each user that will be spawned.
         info!("initializing user states...");
         let mut weighted_users = Vec::new();
-        let mut user_count = 0;
-        loop {
-            for task_sets_index in &weighted_task_sets {
-                let base_url = goose::get_base_url(
-                    self.get_configuration_host(),
-                    self.task_sets[*task_sets_index].host.clone(),
-                    self.host.clone(),
-                );
-                weighted_users.push(GooseUser::new(
-                    self.task_sets[*task_sets_index].task_sets_index,
-                    base_url,
-                    self.task_sets[*task_sets_index].min_wait,
-                    self.task_sets[*task_sets_index].max_wait,
-                    &self.configuration,
-                    self.task_sets_hash,
-                ));
-                user_count += 1;
-                if user_count >= self.users {
-                    trace!("created {} weighted_users", user_count);
-                    return weighted_users;
-                }
-            }
+        for task_sets_index in task_sets_indexes {
+            weighted_users.push(self.build_user(&self.task_sets[task_sets_index]));
         }
+        trace!("created {} weighted_users", weighted_users.len());
+        weighted_users
     }
 
     /// Execute the load test.
@@ -777,19 +1280,148 @@ impl GooseAttack {
     ///     }
     /// ```
     pub fn execute(mut self) {
+        // Configure maximum run time if specified, otherwise run until canceled.
+        if self.configuration.worker {
+            if !self.configuration.run_time.is_empty() {
+                error!("The --run-time option is only available to the manager.");
+                std::process::exit(1);
+            }
+            self.run_time = 0;
+        } else if !self.configuration.run_time.is_empty() {
+            self.run_time = util::parse_timespan(&self.configuration.run_time);
+            info!("run_time = {}", self.run_time);
+        } else {
+            self.run_time = 0;
+        }
+
+        // Configure number of user threads to launch, default to the number of CPU cores available.
+        self.users = match self.configuration.users {
+            Some(u) => {
+                if u == 0 {
+                    if self.configuration.worker {
+                        error!("At least 1 user is required.");
+                        std::process::exit(1);
+                    } else {
+                        0
+                    }
+                } else {
+                    if self.configuration.worker {
+                        error!("The --users option is only available to the manager.");
+                        std::process::exit(1);
+                    }
+                    u
+                }
+            }
+            None => {
+                let u = self.number_of_cpus;
+                if !self.configuration.manager
+                    && !self.configuration.worker
+                    && self.configuration.replay.is_empty()
+                {
+                    info!("concurrent users defaulted to {} (number of CPUs)", u);
+                }
+                u
+            }
+        };
+
+        if !self.configuration.manager && !self.configuration.worker {
+            debug!("users = {}", self.users);
+        }
+
+        // Load the cookies file once, before launching users.
+        if !self.configuration.cookies_file.is_empty() {
+            match cookies::load_cookies_file(&self.configuration.cookies_file) {
+                Ok(c) => self.cookies = c,
+                Err(e) => {
+                    error!("Invalid --cookies-file: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        // Replace the task sets with users replaying the requests in a log.
+        if !self.configuration.replay.is_empty() {
+            let replay = match replay::GooseReplay::load(
+                &self.configuration.replay,
+                &self.configuration.replay_speed,
+            ) {
+                Ok(r) => r,
+                Err(e) => {
+                    error!(
+                        "Failed to load --replay file {}: {}",
+                        self.configuration.replay, e
+                    );
+                    std::process::exit(1);
+                }
+            };
+            info!(
+                "replaying {} requests from {} users in {}",
+                replay.requests(),
+                replay.users(),
+                self.configuration.replay
+            );
+            self.users = replay.users();
+            self.task_sets = Vec::new();
+            self = self.register_taskset(replay.task_set());
+            self.replay = Some(Arc::new(replay));
+        }
+
         // At least one task set is required.
         if self.task_sets.is_empty() {
             error!("No task sets defined.");
             std::process::exit(1);
         }
 
-        if self.configuration.list {
-            // Display task sets and tasks, then exit.
+        // Apply weights from --taskset-weight and --task-weight.
+        self.apply_weights();
+
+        // Confirm --tasksets only names registered task sets, and that at least one task
+        // set is selected.
+        for name in split_list(&self.configuration.tasksets) {
+            if !self.task_sets.iter().any(|t| t.name == name) {
+                error!("Unknown task set '{}' in --tasksets.", name);
+                std::process::exit(1);
+            }
+        }
+        if !self
+            .task_sets
+            .iter()
+            .any(|t| is_task_set_selected(&self.configuration, t))
+        {
+            error!("No task sets are selected by --tags, --exclude-tags and --tasksets.");
+            std::process::exit(1);
+        }
+
+        if self.configuration.list && self.configuration.list_format == "json" {
+            // Display the selected task sets and tasks as JSON, then exit.
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&self.list_json()).unwrap()
+            );
+            std::process::exit(0);
+        } else if self.configuration.list {
+            // Display the selected task sets and tasks, then exit.
             println!("Available tasks:");
-            for task_set in self.task_sets {
-                println!(" - {} (weight: {})", task_set.name, task_set.weight);
-                for task in task_set.tasks {
-                    println!("    o {} (weight: {})", task.name, task.weight);
+            for task_set in &self.task_sets {
+                if !is_task_set_selected(&self.configuration, task_set) {
+                    continue;
+                }
+                println!(
+                    " - {} (weight: {}){}",
+                    task_set.name,
+                    task_set.weight,
+                    format_tags(&task_set.tags)
+                );
+                for task in &task_set.tasks {
+                    if !is_task_selected(&self.configuration, task_set, task) {
+                        continue;
+                    }
+                    println!(
+                        "    o {} (weight: {}){}",
+                        task.name,
+                        task.weight,
+                        format_tags(&task.tags)
+                    );
                 }
             }
             std::process::exit(0);
@@ -834,11 +1466,16 @@ impl GooseAttack {
                 std::process::exit(1);
             }
 
-            if self.configuration.host != "" {
+            if !self.configuration.host.is_empty() {
                 error!("The --host option is only available to the manager");
                 std::process::exit(1);
             }
 
+            if !self.configuration.named_host.is_empty() {
+                error!("The --named-host option is only available to the manager");
+                std::process::exit(1);
+            }
+
             if self.configuration.manager_bind_host != "0.0.0.0" {
                 error!("The --manager-bind-host option is only available to the manager");
                 std::process::exit(1);
@@ -891,6 +1528,35 @@ impl GooseAttack {
         }
         debug!("hatch_rate = {}", hatch_rate);
 
+        // Confirm named hosts are valid, and each name is only set once.
+        let mut named_hosts = HashSet::new();
+        for named_host in &self.configuration.named_host {
+            match goose::parse_named_host(named_host) {
+                Ok((name, host)) => {
+                    if !named_hosts.insert(name.to_string()) {
+                        error!("The --named-host {}= option can only be set once.", name);
+                        std::process::exit(1);
+                    }
+                    if is_valid_host(host) {
+                        info!("{} host configured: {}", name, host);
+                    }
+                }
+                Err(e) => {
+                    error!("Invalid --named-host {}: {}", named_host, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        if !self.configuration.stats_host.is_empty()
+            && !named_hosts.contains(&self.configuration.stats_host)
+        {
+            error!(
+                "The --stats-host option must match a named host set with --named-host {}=.",
+                self.configuration.stats_host
+            );
+            std::process::exit(1);
+        }
+
         // Confirm there's either a global host, or each task set has a host defined.
         if self.configuration.host.is_empty() {
             for task_set in &self.task_sets {
@@ -919,38 +1585,76 @@ impl GooseAttack {
             info!("global host configured: {}", self.configuration.host);
         }
 
-        // Apply weights to tasks in each task set.
-        for task_set in &mut self.task_sets {
-            let (weighted_on_start_tasks, weighted_tasks, weighted_on_stop_tasks) =
-                weight_tasks(&task_set);
-            task_set.weighted_on_start_tasks = weighted_on_start_tasks;
-            task_set.weighted_tasks = weighted_tasks;
-            task_set.weighted_on_stop_tasks = weighted_on_stop_tasks;
-            debug!(
-                "weighted {} on_start: {:?} tasks: {:?} on_stop: {:?}",
-                task_set.name,
-                task_set.weighted_on_start_tasks,
-                task_set.weighted_tasks,
-                task_set.weighted_on_stop_tasks
-            );
+        // Load all feeders.
+        for feeder in self.feeders.values_mut() {
+            if let Err(e) = feeder.load() {
+                error!("Failed to load feeder {}: {}", feeder.name, e);
+                std::process::exit(1);
+            }
+        }
+
+        // Confirm all request name rules are valid, compiling them once for all users.
+        match goose::get_request_name_rules(&self.configuration.request_name_rule) {
+            Ok(rules) => self.request_name_rules = Arc::new(rules),
+            Err(e) => {
+                error!("Invalid --request-name-rule: {}", e);
+                std::process::exit(1);
+            }
+        }
+
+        // Confirm the global proxy and any task set proxies are valid.
+        if !self.configuration.proxy.is_empty() && is_valid_proxy(&self.configuration.proxy) {
+            info!("global proxy configured: {}", self.configuration.proxy);
+        }
+        for task_set in &self.task_sets {
+            if let Some(proxy) = &task_set.proxy {
+                if is_valid_proxy(proxy) {
+                    info!("proxy for {} configured: {}", task_set.name, proxy);
+                }
+            }
+        }
+
+        // Apply weights to the selected tasks in each task set.
+        set_weighted_tasks(&mut self.task_sets, &self.configuration);
+
+        // Run each selected task once and report the results instead of load testing.
+        if self.configuration.validate {
+            let mut rt = tokio::runtime::Runtime::new().unwrap();
+            if !rt.block_on(validate::validate_main(self)) {
+                std::process::exit(1);
+            }
+            return;
         }
 
         // Allocate a state for each of the users we are about to start.
         if !self.configuration.worker {
             self.weighted_users = self.weight_task_set_users();
+            self.print_mix();
+            if self.configuration.connection_pool == GooseConnectionPool::Shared {
+                goose::share_client(&mut self.weighted_users);
+            }
+            let feeders = Arc::new(std::mem::take(&mut self.feeders));
+            for user in &mut self.weighted_users {
+                user.feeders = feeders.clone();
+                user.options = self.options.clone();
+                user.replay = self.replay.clone();
+            }
         }
 
         // Calculate a unique hash for the current load test.
-        let mut s = DefaultHasher::new();
-        self.task_sets.hash(&mut s);
-        self.task_sets_hash = s.finish();
+        self.task_sets_hash = load_test_hash(&self.task_sets, &self.configuration);
         debug!("task_sets_hash: {}", self.task_sets_hash);
 
         // Our load test is officially starting.
         let started = time::Instant::now();
         // Spawn users at hatch_rate per second, or one every 1 / hatch_rate fraction of a second.
         let sleep_float = 1.0 / hatch_rate as f32;
-        let sleep_duration = time::Duration::from_secs_f32(sleep_float);
+        // All replaying users start at once, and wait until their first request is due.
+        let sleep_duration = if self.replay.is_some() {
+            time::Duration::from_secs(0)
+        } else {
+            time::Duration::from_secs_f32(sleep_float)
+        };
 
         // Start goose in manager mode.
         if self.configuration.manager {
@@ -989,8 +1693,17 @@ impl GooseAttack {
         }
 
         if !self.configuration.no_stats && !self.configuration.worker {
+            // The manager samples bandwidth relative to when all workers connected.
+            if !self.configuration.manager {
+                stats::record_bandwidth(&mut self, started.elapsed().as_secs() as usize);
+            }
             stats::print_final_stats(&self, started.elapsed().as_secs() as usize);
         }
+
+        if let Some(reason) = &self.aborted {
+            error!("load test aborted: {}", reason);
+            std::process::exit(abort::ABORT_EXIT_CODE);
+        }
     }
 
     /// Helper to wrap configured host in Option<> if set.
@@ -1002,29 +1715,70 @@ impl GooseAttack {
         }
     }
 
+    /// Once `--max-request-names` request keys are tracked, new request keys are
+    /// counted in an "other" bucket. Warns the first time this happens.
+    pub(crate) fn is_request_name_capped(&mut self, key: &str) -> bool {
+        let max_request_names = self.configuration.max_request_names;
+        if max_request_names == 0
+            || self.merged_requests.len() < max_request_names
+            || self.merged_requests.contains_key(key)
+        {
+            return false;
+        }
+        if !self.request_names_capped {
+            warn!(
+                "more than {} request names, counting new request names as \"other\": normalize request names with --request-name-rule, or raise --max-request-names",
+                max_request_names
+            );
+            self.request_names_capped = true;
+        }
+        true
+    }
+
+    /// Helper to build the key statistics are merged into, grouping requests by named host.
+    fn get_request_key(raw_request: &GooseRawRequest) -> String {
+        if raw_request.host.is_empty() {
+            format!("{:?} {}", raw_request.method, raw_request.name)
+        } else {
+            format!(
+                "{:?} {}:{}",
+                raw_request.method, raw_request.host, raw_request.name
+            )
+        }
+    }
+
     /// Helper to create CSV-formatted logs.
     fn prepare_csv(raw_request: &GooseRawRequest, header: &mut bool) -> String {
         let body = format!(
-            // Put quotes around name, url and final_url as they are strings.
-            "{},{:?},\"{}\",\"{}\",\"{}\",{},{},{},{},{},{}",
+            // Put quotes around name, url, final_url and error as they are strings, and
+            // escape any quotes they contain by doubling them.
+            "{},{:?},\"{}\",\"{}\",\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{},\"{}\"",
             raw_request.elapsed,
             raw_request.method,
-            raw_request.name,
-            raw_request.url,
-            raw_request.final_url,
+            raw_request.name.replace('"', "\"\""),
+            raw_request.url.replace('"', "\"\""),
+            raw_request.final_url.replace('"', "\"\""),
             raw_request.redirected,
             raw_request.response_time,
             raw_request.status_code,
             raw_request.success,
             raw_request.update,
-            raw_request.user
+            raw_request.user,
+            raw_request.request_bytes,
+            raw_request.response_bytes,
+            raw_request.response_decompressed_bytes,
+            raw_request.connection_reused_estimate,
+            raw_request.http_version,
+            raw_request.source_address,
+            raw_request.error_kind,
+            raw_request.error.replace('"', "\"\"")
         );
         // Concatenate the header before the body one time.
         if *header {
             *header = false;
             format!(
                 // No quotes needed in header.
-                "{},{},{},{},{},{},{},{},{},{},{}\n",
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
                 "elapsed",
                 "method",
                 "name",
@@ -1035,7 +1789,15 @@ impl GooseAttack {
                 "status_code",
                 "success",
                 "update",
-                "user"
+                "user",
+                "request_bytes",
+                "response_bytes",
+                "response_decompressed_bytes",
+                "connection_reused_estimate",
+                "http_version",
+                "source_address",
+                "error_kind",
+                "error"
             ) + &body
         } else {
             body
@@ -1065,7 +1827,9 @@ impl GooseAttack {
                     // Create a one-time-use User to run the test_start_task.
                     let base_url =
                         goose::get_base_url(self.get_configuration_host(), None, self.host.clone());
-                    let user = GooseUser::single(base_url, &self.configuration);
+                    let mut user = GooseUser::single(base_url, &self.configuration);
+                    user.init_cookies(&self.cookies);
+                    user.request_name_rules = Arc::clone(&self.request_name_rules);
                     let function = t.function;
                     function(&user).await;
                 }
@@ -1122,8 +1886,13 @@ impl GooseAttack {
             thread_user.weighted_on_stop_tasks = self.task_sets[thread_user.task_sets_index]
                 .weighted_on_stop_tasks
                 .clone();
-            // Remember which task group this user is using.
-            thread_user.weighted_users_index = self.active_users;
+            // Remember which task group this user is using. Workers keep the index the
+            // manager assigned, so users are numbered the same across the gaggle.
+            if !self.configuration.worker {
+                thread_user.weighted_users_index = self.active_users;
+            }
+            thread_user.init_rng();
+            thread_user.init_source_address();
 
             // Create a per-thread channel allowing parent thread to control child threads.
             let (parent_sender, thread_receiver): (
@@ -1150,6 +1919,10 @@ impl GooseAttack {
 
             let is_worker = self.configuration.worker;
 
+            // Track the iterations completed by users of each task set.
+            let task_sets_index = thread_user.task_sets_index;
+            self.iterations.entry(task_sets_index).or_insert(0);
+
             // Launch a new user.
             let user = tokio::spawn(user::user_main(
                 thread_number,
@@ -1159,7 +1932,7 @@ impl GooseAttack {
                 is_worker,
             ));
 
-            users.push(user);
+            users.push((task_sets_index, user));
             self.active_users += 1;
             debug!("sleeping {:?} milliseconds...", sleep_duration);
             tokio::time::delay_for(sleep_duration).await;
@@ -1186,6 +1959,7 @@ impl GooseAttack {
         // Determine when to display running statistics (if enabled).
         let mut statistics_timer = time::Instant::now();
         let mut display_running_statistics = false;
+        let mut record_bandwidth = false;
 
         // Prepare an asynchronous buffered file writer for stats_log_file (if enabled).
         let mut stats_log_file = None;
@@ -1202,6 +1976,13 @@ impl GooseAttack {
             }
         }
 
+        // Workers leave evaluating abort conditions to the manager.
+        let mut abort_monitor = if self.configuration.worker {
+            None
+        } else {
+            abort::GooseAbortMonitor::new(&self.configuration)
+        };
+
         // If logging stats to CSV, use this flag to write header; otherwise it's ignored.
         let mut header = true;
         loop {
@@ -1210,6 +1991,7 @@ impl GooseAttack {
                 // Synchronize statistics from user threads into parent.
                 if util::timer_expired(statistics_timer, RUNNING_STATS_EVERY) {
                     statistics_timer = time::Instant::now();
+                    record_bandwidth = true;
                     if !self.configuration.only_summary {
                         display_running_statistics = true;
                     }
@@ -1220,7 +2002,18 @@ impl GooseAttack {
                 let mut message = parent_receiver.try_recv();
                 while message.is_ok() {
                     received_message = true;
-                    let raw_request = message.unwrap();
+                    let mut raw_request = message.unwrap();
+
+                    if let Some(monitor) = abort_monitor.as_mut() {
+                        monitor.record_request(&raw_request, started.elapsed().as_secs() as usize);
+                    }
+
+                    // Count new request names as "other" once --max-request-names is
+                    // reached, in the statistics log as well.
+                    if self.is_request_name_capped(&GooseAttack::get_request_key(&raw_request)) {
+                        raw_request.name = "other".to_string();
+                        raw_request.host = "".to_string();
+                    }
 
                     // Options should appear above, search for formatted_log.
                     let formatted_log = match self.configuration.stats_log_format.as_str() {
@@ -1245,25 +2038,41 @@ impl GooseAttack {
                         }
                     }
 
-                    let key = format!("{:?} {}", raw_request.method, raw_request.name);
+                    let key = GooseAttack::get_request_key(&raw_request);
                     let mut merge_request = match self.merged_requests.get(&key) {
                         Some(m) => m.clone(),
-                        None => GooseRequest::new(&raw_request.name, raw_request.method, 0),
+                        None => {
+                            let mut request =
+                                GooseRequest::new(&raw_request.name, raw_request.method, 0);
+                            request.host = raw_request.host.clone();
+                            request
+                        }
                     };
                     // Handle a statistics update.
                     if raw_request.update {
                         if raw_request.success {
                             merge_request.success_count += 1;
                             merge_request.fail_count -= 1;
+                            merge_request.unset_error(&raw_request.error_kind);
                         } else {
                             merge_request.success_count -= 1;
                             merge_request.fail_count += 1;
+                            merge_request.set_error(&raw_request.error_kind);
                         }
                     }
                     // Store a new statistic.
                     else {
                         merge_request.set_response_time(raw_request.response_time);
                         merge_request.set_status_code(raw_request.status_code);
+                        merge_request.set_bytes(
+                            raw_request.request_bytes,
+                            raw_request.response_bytes,
+                            raw_request.response_decompressed_bytes,
+                        );
+                        merge_request.set_connection(raw_request.connection_reused_estimate);
+                        merge_request.set_http_version(&raw_request.http_version);
+                        merge_request.set_source_address(&raw_request.source_address);
+                        merge_request.set_error(&raw_request.error_kind);
                         if raw_request.success {
                             merge_request.success_count += 1;
                         } else {
@@ -1282,7 +2091,10 @@ impl GooseAttack {
                         // Push all statistics to manager process.
                         if !worker::push_stats_to_manager(
                             &socket.clone().unwrap(),
-                            &self.merged_requests.clone(),
+                            &manager::GooseWorkerMessage {
+                                requests: self.merged_requests.clone(),
+                                ..Default::default()
+                            },
                             true,
                         ) {
                             // EXIT received, cancel.
@@ -1293,16 +2105,62 @@ impl GooseAttack {
                     }
                 }
 
+                // Stop gracefully if an abort condition is met.
+                if let Some(monitor) = abort_monitor.as_mut() {
+                    if let Some(reason) = monitor.check(started.elapsed().as_secs() as usize) {
+                        error!("aborting load test: {}", reason);
+                        self.aborted = Some(reason);
+                        abort_monitor = None;
+                        canceled.store(true, Ordering::SeqCst);
+                    }
+                }
+
                 // Flush statistics collected prior to all user threads running
                 if self.configuration.reset_stats && !statistics_reset {
                     info!("statistics reset...");
                     self.merged_requests = HashMap::new();
+                    // Bandwidth over time is measured from the reset.
+                    self.bandwidth = vec![stats::GooseBandwidthSample {
+                        elapsed: started.elapsed().as_secs() as usize,
+                        ..Default::default()
+                    }];
                     statistics_reset = true;
                 }
             }
 
-            if util::timer_expired(started, self.run_time) || canceled.load(Ordering::SeqCst) {
-                if self.configuration.worker {
+            // Record the iterations completed by users that have exited.
+            let mut running_users = Vec::new();
+            for (task_sets_index, mut user) in users {
+                match futures::FutureExt::now_or_never(&mut user) {
+                    Some(Ok(iterations)) => {
+                        *self.iterations.entry(task_sets_index).or_insert(0) += iterations;
+                    }
+                    Some(Err(e)) => warn!("user exited unexpectedly: {}", e),
+                    None => running_users.push((task_sets_index, user)),
+                }
+            }
+            users = running_users;
+
+            // Stop once all users have exited, for example after completing --iterations.
+            let users_exited = users.is_empty();
+            if util::timer_expired(started, self.run_time)
+                || canceled.load(Ordering::SeqCst)
+                || users_exited
+            {
+                if users_exited {
+                    if self.configuration.worker {
+                        info!(
+                            "[{}] all users exited after {} seconds...",
+                            get_worker_id(),
+                            started.elapsed().as_secs()
+                        );
+                    } else {
+                        info!(
+                            "all users exited after {} seconds...",
+                            started.elapsed().as_secs()
+                        );
+                    }
+                } else if self.configuration.worker {
                     info!(
                         "[{}] stopping after {} seconds...",
                         get_worker_id(),
@@ -1311,6 +2169,10 @@ impl GooseAttack {
                 } else {
                     info!("stopping after {} seconds...", started.elapsed().as_secs());
                 }
+                // Don't wait for users replaying requests that aren't due yet.
+                if let Some(replay) = &self.replay {
+                    replay.stop();
+                }
                 for (index, send_to_user) in user_channels.iter().enumerate() {
                     match send_to_user.send(GooseUserCommand::EXIT) {
                         Ok(_) => {
@@ -1326,7 +2188,16 @@ impl GooseAttack {
                 } else {
                     info!("waiting for users to exit");
                 }
-                futures::future::join_all(users).await;
+                let (task_sets_indexes, users): (Vec<usize>, Vec<_>) = users.into_iter().unzip();
+                let exited = futures::future::join_all(users).await;
+                for (task_sets_index, iterations) in task_sets_indexes.iter().zip(exited) {
+                    match iterations {
+                        Ok(iterations) => {
+                            *self.iterations.entry(*task_sets_index).or_insert(0) += iterations;
+                        }
+                        Err(e) => warn!("user exited unexpectedly: {}", e),
+                    }
+                }
                 debug!("all users exited");
 
                 if !self.configuration.debug_log_file.is_empty() {
@@ -1342,14 +2213,33 @@ impl GooseAttack {
                 if !self.configuration.no_stats {
                     let mut message = parent_receiver.try_recv();
                     while message.is_ok() {
-                        let raw_request = message.unwrap();
-                        let key = format!("{:?} {}", raw_request.method, raw_request.name);
+                        let mut raw_request = message.unwrap();
+                        if self.is_request_name_capped(&GooseAttack::get_request_key(&raw_request))
+                        {
+                            raw_request.name = "other".to_string();
+                            raw_request.host = "".to_string();
+                        }
+                        let key = GooseAttack::get_request_key(&raw_request);
                         let mut merge_request = match self.merged_requests.get(&key) {
                             Some(m) => m.clone(),
-                            None => GooseRequest::new(&raw_request.name, raw_request.method, 0),
+                            None => {
+                                let mut request =
+                                    GooseRequest::new(&raw_request.name, raw_request.method, 0);
+                                request.host = raw_request.host.clone();
+                                request
+                            }
                         };
                         merge_request.set_response_time(raw_request.response_time);
                         merge_request.set_status_code(raw_request.status_code);
+                        merge_request.set_bytes(
+                            raw_request.request_bytes,
+                            raw_request.response_bytes,
+                            raw_request.response_decompressed_bytes,
+                        );
+                        merge_request.set_connection(raw_request.connection_reused_estimate);
+                        merge_request.set_http_version(&raw_request.http_version);
+                        merge_request.set_source_address(&raw_request.source_address);
+                        merge_request.set_error(&raw_request.error_kind);
                         if raw_request.success {
                             merge_request.success_count += 1;
                         } else {
@@ -1365,10 +2255,14 @@ impl GooseAttack {
                 {
                     // As worker, push statistics up to manager.
                     if self.configuration.worker {
-                        // Push all statistics to manager process.
+                        // Push all statistics and completed iterations to manager process.
                         worker::push_stats_to_manager(
                             &socket.clone().unwrap(),
-                            &self.merged_requests.clone(),
+                            &manager::GooseWorkerMessage {
+                                requests: self.merged_requests.clone(),
+                                finished: true,
+                                iterations: self.iterations.clone(),
+                            },
                             true,
                         );
                         // No need to reset local stats, the worker is exiting.
@@ -1379,6 +2273,12 @@ impl GooseAttack {
                 break;
             }
 
+            // Sample bandwidth over time after sync, even if not displaying it yet.
+            if record_bandwidth {
+                record_bandwidth = false;
+                stats::record_bandwidth(&mut self, started.elapsed().as_secs() as usize);
+            }
+
             // If enabled, display running statistics after sync
             if display_running_statistics {
                 display_running_statistics = false;
@@ -1397,7 +2297,9 @@ impl GooseAttack {
                     let base_url =
                         goose::get_base_url(self.get_configuration_host(), None, self.host.clone());
                     // Create a one-time-use user to run the test_stop_task.
-                    let user = GooseUser::single(base_url, &self.configuration);
+                    let mut user = GooseUser::single(base_url, &self.configuration);
+                    user.init_cookies(&self.cookies);
+                    user.request_name_rules = Arc::clone(&self.request_name_rules);
                     let function = t.function;
                     function(&user).await;
                 }
@@ -1423,10 +2325,18 @@ impl GooseAttack {
 #[derive(StructOpt, Debug, Default, Clone, Serialize, Deserialize)]
 #[structopt(name = "Goose")]
 pub struct GooseConfiguration {
+    /// Loads options from a TOML file, overridden by GOOSE_* environment variables and the command line
+    #[structopt(long, default_value = "")]
+    pub config_file: String,
+
     /// Host to load test, for example: http://10.21.32.33
     #[structopt(short = "H", long, required = false, default_value = "")]
     pub host: String,
 
+    /// Named host to load test, for example: api=http://10.21.32.34, can be repeated
+    #[structopt(long, number_of_values = 1)]
+    pub named_host: Vec<String>,
+
     /// Number of concurrent Goose users (defaults to available CPUs).
     #[structopt(short, long)]
     pub users: Option<usize>,
@@ -1439,6 +2349,38 @@ pub struct GooseConfiguration {
     #[structopt(short = "t", long, required = false, default_value = "")]
     pub run_time: String,
 
+    /// Stop each user after running its task set this many times (0 for no limit)
+    #[structopt(long, default_value = "0")]
+    pub iterations: usize,
+
+    /// Seeds each user's random number generator, so tasks run in the same order every run
+    #[structopt(long)]
+    pub seed: Option<u64>,
+
+    /// Replays the requests of each user in a stats log or access log, instead of running task sets
+    #[structopt(long, required = false, default_value = "")]
+    pub replay: String,
+
+    /// Replays requests this many times faster than originally made, or "asap" as fast as possible
+    #[structopt(long, required = false, default_value = "1")]
+    pub replay_speed: String,
+
+    /// Aborts if more than this percent of requests fail within --abort-window (0 to disable)
+    #[structopt(long, default_value = "0")]
+    pub abort_fail_rate: usize,
+
+    /// Aborts if the 95th percentile response time within --abort-window exceeds this many milliseconds (0 to disable)
+    #[structopt(long, default_value = "0")]
+    pub abort_p95: usize,
+
+    /// Aborts after this many consecutive transport errors (0 to disable)
+    #[structopt(long, default_value = "0")]
+    pub abort_transport_errors: usize,
+
+    /// Sliding window in seconds for --abort-fail-rate and --abort-p95
+    #[structopt(long, default_value = "60")]
+    pub abort_window: usize,
+
     /// Don't print stats in the console
     #[structopt(long)]
     pub no_stats: bool,
@@ -1447,6 +2389,18 @@ pub struct GooseConfiguration {
     #[structopt(long)]
     pub status_codes: bool,
 
+    /// Normalizes request names matching a regex, as regex=>template
+    #[structopt(long, number_of_values = 1)]
+    pub request_name_rule: Vec<String>,
+
+    /// Counts new request names as "other" after this many, 0 for no limit
+    #[structopt(long, default_value = "1000")]
+    pub max_request_names: usize,
+
+    /// Only displays statistics for requests made against this named host
+    #[structopt(long, default_value = "")]
+    pub stats_host: String,
+
     /// Only prints summary stats
     #[structopt(long)]
     pub only_summary: bool,
@@ -1459,6 +2413,34 @@ pub struct GooseConfiguration {
     #[structopt(short, long)]
     pub list: bool,
 
+    /// Format of --list output ('text' or 'json')
+    #[structopt(long, default_value = "text")]
+    pub list_format: String,
+
+    /// Runs each task once with one user per task set, reporting which tasks pass
+    #[structopt(long)]
+    pub validate: bool,
+
+    /// Only runs task sets and tasks with one of these comma-separated tags
+    #[structopt(long, default_value = "")]
+    pub tags: String,
+
+    /// Doesn't run task sets and tasks with any of these comma-separated tags
+    #[structopt(long, default_value = "")]
+    pub exclude_tags: String,
+
+    /// Only runs these comma-separated task sets
+    #[structopt(long, default_value = "")]
+    pub tasksets: String,
+
+    /// Overrides the weight of a task set, as TaskSet=weight
+    #[structopt(long, number_of_values = 1)]
+    pub taskset_weight: Vec<String>,
+
+    /// Overrides the weight of a task, as TaskSet.Task=weight
+    #[structopt(long, number_of_values = 1)]
+    pub task_weight: Vec<String>,
+
     // The number of occurrences of the `v/verbose` flag
     /// Debug level (-v, -vv, -vvv, etc.)
     #[structopt(short = "v", long, parse(from_occurrences))]
@@ -1493,6 +2475,66 @@ pub struct GooseConfiguration {
     #[structopt(long)]
     pub sticky_follow: bool,
 
+    /// Disables keep-alive, opening a new connection for each request
+    #[structopt(long)]
+    pub no_keepalive: bool,
+
+    /// Maximum idle connections kept open per host (defaults to no limit)
+    #[structopt(long)]
+    pub max_idle_connections: Option<usize>,
+
+    /// Seconds an idle connection is kept open (defaults to 90)
+    #[structopt(long)]
+    pub idle_timeout: Option<usize>,
+
+    /// Connection pool per user or shared by all users
+    #[structopt(long, default_value = "user", possible_values = &["user", "shared"])]
+    pub connection_pool: GooseConnectionPool,
+
+    /// HTTP version, '2' with prior knowledge [default: auto]
+    #[structopt(long, possible_values = &["auto", "1.1", "2"])]
+    pub http_version: Option<GooseHttpVersion>,
+
+    /// Pre-seeds each user's cookies from a Netscape or JSON cookie file
+    #[structopt(long, default_value = "")]
+    pub cookies_file: String,
+
+    /// Binds users round-robin to comma-separated local source addresses
+    #[structopt(long, default_value = "")]
+    pub source_addresses: String,
+
+    /// Proxy for all requests, for example http://10.0.0.1:3128 or socks5://10.0.0.1:1080
+    #[structopt(long, default_value = "")]
+    pub proxy: String,
+
+    /// Sets a timeout in seconds for each request, including reading the response body
+    #[structopt(long)]
+    pub timeout: Option<usize>,
+
+    /// Sets a timeout in seconds for opening new connections
+    #[structopt(long)]
+    pub connect_timeout: Option<usize>,
+
+    /// Sets the User-Agent header of all requests
+    #[structopt(long, default_value = "")]
+    pub user_agent: String,
+
+    /// Adds a default header to all requests, as "Name: value"
+    #[structopt(long, number_of_values = 1)]
+    pub header: Vec<String>,
+
+    /// Requests gzip or deflate compressed responses
+    #[structopt(long)]
+    pub gzip: bool,
+
+    /// Maximum number of redirects followed, 0 to not follow redirects [default: 10]
+    #[structopt(long)]
+    pub max_redirects: Option<usize>,
+
+    /// Accepts invalid TLS certificates, such as self-signed certificates
+    #[structopt(long)]
+    pub accept_invalid_certs: bool,
+
     /// Enables manager mode
     #[structopt(long)]
     pub manager: bool,
@@ -1526,9 +2568,218 @@ pub struct GooseConfiguration {
     pub manager_port: u16,
 }
 
-/// Returns a sequenced bucket of weighted usize pointers to Goose Tasks
+/// Configuration options a load test can set defaults for with `set_default()`. Each
+/// variant sets the default of the command line option with the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GooseDefault {
+    Host,
+    NamedHost,
+    Users,
+    HatchRate,
+    RunTime,
+    Iterations,
+    Seed,
+    Replay,
+    ReplaySpeed,
+    AbortFailRate,
+    AbortP95,
+    AbortTransportErrors,
+    AbortWindow,
+    NoStats,
+    StatusCodes,
+    RequestNameRule,
+    MaxRequestNames,
+    StatsHost,
+    OnlySummary,
+    ResetStats,
+    ListFormat,
+    Validate,
+    Tags,
+    ExcludeTags,
+    Tasksets,
+    TasksetWeight,
+    TaskWeight,
+    Verbose,
+    LogLevel,
+    LogFile,
+    StatsLogFile,
+    StatsLogFormat,
+    DebugLogFile,
+    DebugLogFormat,
+    StickyFollow,
+    NoKeepalive,
+    MaxIdleConnections,
+    IdleTimeout,
+    ConnectionPool,
+    HttpVersion,
+    CookiesFile,
+    SourceAddresses,
+    Proxy,
+    Timeout,
+    ConnectTimeout,
+    UserAgent,
+    Header,
+    Gzip,
+    MaxRedirects,
+    AcceptInvalidCerts,
+    Manager,
+    NoHashCheck,
+    ExpectWorkers,
+    ManagerBindHost,
+    ManagerBindPort,
+    Worker,
+    ManagerHost,
+    ManagerPort,
+}
+impl GooseDefault {
+    /// The name of the `GooseConfiguration` field this default is for.
+    fn option(self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Set defaults for configuration options from the load test, which are used when an
+/// option is not set on the command line, in the environment or in a config file.
+/// Options that can be repeated, such as `GooseDefault::NamedHost`, can be set more than
+/// once. Flags set to true by default can't be disabled at run time.
+///
+/// # Example
+/// ```rust,no_run
+///     use goose::prelude::*;
+///
+///     GooseAttack::initialize()
+///         .set_default(GooseDefault::Users, 50)
+///         .set_default(GooseDefault::RunTime, "10m")
+///         .set_default(GooseDefault::StatsLogFormat, "csv");
+/// ```
+pub trait GooseDefaultType<T> {
+    fn set_default(self, key: GooseDefault, value: T) -> Self;
+}
+impl GooseDefaultType<&str> for GooseAttack {
+    fn set_default(self, key: GooseDefault, value: &str) -> Self {
+        self.add_default(key, serde_json::Value::from(value))
+    }
+}
+impl GooseDefaultType<usize> for GooseAttack {
+    fn set_default(self, key: GooseDefault, value: usize) -> Self {
+        self.add_default(key, serde_json::Value::from(value))
+    }
+}
+impl GooseDefaultType<bool> for GooseAttack {
+    fn set_default(self, key: GooseDefault, value: bool) -> Self {
+        self.add_default(key, serde_json::Value::from(value))
+    }
+}
+
+/// Calculate a unique hash for the load test, including which task sets and tasks are
+/// selected to run, so the manager can confirm workers run the same load test.
+fn load_test_hash(task_sets: &[GooseTaskSet], configuration: &GooseConfiguration) -> u64 {
+    let mut s = DefaultHasher::new();
+    task_sets.hash(&mut s);
+    configuration.tags.hash(&mut s);
+    configuration.exclude_tags.hash(&mut s);
+    configuration.tasksets.hash(&mut s);
+    s.finish()
+}
+
+/// Split a `--taskset-weight` or `--task-weight` value into a name and a weight.
+fn parse_weight(value: &str) -> Result<(&str, usize), String> {
+    let mut parts = value.rsplitn(2, '=');
+    let weight = parts.next().unwrap_or("").trim();
+    let name = match parts.next() {
+        Some(n) => n.trim(),
+        None => return Err(format!("expected 'name=weight', found '{}'", value)),
+    };
+    match weight.parse::<usize>() {
+        Ok(w) if w > 0 => Ok((name, w)),
+        _ => Err(format!("weight must be 1 or more, found '{}'", weight)),
+    }
+}
+
+/// Splits a comma-separated option such as `--tags`, ignoring empty values.
+fn split_list(option: &str) -> Vec<&str> {
+    option
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Returns true if a task is selected to run by `--tags` and `--exclude-tags`. Tasks
+/// inherit the tags of their task set. On start and on stop tasks don't need to match
+/// `--tags`, so users still run them around the selected tasks.
+fn is_task_selected(
+    configuration: &GooseConfiguration,
+    task_set: &GooseTaskSet,
+    task: &GooseTask,
+) -> bool {
+    let tags: Vec<&str> = task_set
+        .tags
+        .iter()
+        .chain(task.tags.iter())
+        .map(|t| t.as_str())
+        .collect();
+    let exclude_tags = split_list(&configuration.exclude_tags);
+    if tags.iter().any(|t| exclude_tags.contains(t)) {
+        return false;
+    }
+    let include_tags = split_list(&configuration.tags);
+    include_tags.is_empty()
+        || task.on_start
+        || task.on_stop
+        || tags.iter().any(|t| include_tags.contains(t))
+}
+
+/// Returns true if a task set is selected to run by `--tasksets`, `--tags` and
+/// `--exclude-tags`. With `--tags`, a task set must have at least one selected task
+/// other than on start and on stop tasks.
+fn is_task_set_selected(configuration: &GooseConfiguration, task_set: &GooseTaskSet) -> bool {
+    let tasksets = split_list(&configuration.tasksets);
+    if !tasksets.is_empty() && !tasksets.contains(&task_set.name.as_str()) {
+        return false;
+    }
+    let all_tags = split_list(&configuration.tags).is_empty();
+    task_set.tasks.iter().any(|task| {
+        is_task_selected(configuration, task_set, task)
+            && (all_tags || (!task.on_start && !task.on_stop))
+    })
+}
+
+/// Formats tags for `--list`.
+fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "".to_string()
+    } else {
+        format!(" [tags: {}]", tags.join(", "))
+    }
+}
+
+/// Apply weights to the selected tasks in each task set.
+fn set_weighted_tasks(task_sets: &mut [GooseTaskSet], configuration: &GooseConfiguration) {
+    for task_set in task_sets {
+        let (weighted_on_start_tasks, weighted_tasks, weighted_on_stop_tasks) =
+            weight_tasks(task_set, configuration);
+        task_set.weighted_on_start_tasks = weighted_on_start_tasks;
+        task_set.weighted_tasks = weighted_tasks;
+        task_set.weighted_on_stop_tasks = weighted_on_stop_tasks;
+        debug!(
+            "weighted {} on_start: {:?} tasks: {:?} on_stop: {:?}",
+            task_set.name,
+            task_set.weighted_on_start_tasks,
+            task_set.weighted_tasks,
+            task_set.weighted_on_stop_tasks
+        );
+    }
+}
+
+/// Returns a sequenced bucket of weighted usize pointers to the Goose Tasks selected to run.
 fn weight_tasks(
     task_set: &GooseTaskSet,
+    configuration: &GooseConfiguration,
 ) -> (WeightedGooseTasks, WeightedGooseTasks, WeightedGooseTasks) {
     trace!("weight_tasks for {}", task_set.name);
 
@@ -1543,6 +2794,13 @@ fn weight_tasks(
     let mut v: usize;
     // Handle ordering of tasks.
     for task in &task_set.tasks {
+        if !is_task_selected(configuration, task_set, task) {
+            debug!(
+                "{} task {} is not selected to run",
+                task_set.name, task.name
+            );
+            continue;
+        }
         if task.sequence > 0 {
             if task.on_start {
                 if let Some(sequence) = sequenced_on_start_tasks.get_mut(&task.sequence) {
@@ -1728,6 +2986,16 @@ fn is_valid_host(host: &str) -> bool {
         }
     }
 }
+
+fn is_valid_proxy(proxy: &str) -> bool {
+    match reqwest::Proxy::all(proxy) {
+        Ok(_) => true,
+        Err(e) => {
+            error!("invalid proxy '{}': {}", proxy, e);
+            std::process::exit(1);
+        }
+    }
+}
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1742,4 +3010,234 @@ mod test {
         assert_eq!(is_valid_host("foo://example.com"), true);
         assert_eq!(is_valid_host("file:///path/to/file"), true);
     }
+
+    #[derive(StructOpt)]
+    struct TestOptions {
+        #[structopt(long, default_value = "default")]
+        tenant: String,
+    }
+
+    #[test]
+    fn load_test_options() {
+        let matches = GooseConfiguration::augment_options(TestOptions::clap())
+            .get_matches_from(vec!["goose", "--tenant", "acme", "--users", "3"]);
+        let (configuration, _) = GooseConfiguration::load_from_matches(&matches).unwrap();
+        assert_eq!(configuration.users, Some(3));
+
+        let mut goose_attack = GooseAttack::initialize_with_config(configuration);
+        assert!(goose_attack.get_options::<TestOptions>().is_none());
+        goose_attack.options = Some(Arc::new(TestOptions::from_clap(&matches)));
+        assert_eq!(
+            goose_attack.get_options::<TestOptions>().unwrap().tenant,
+            "acme"
+        );
+        assert!(goose_attack.get_options::<String>().is_none());
+    }
+
+    #[test]
+    fn task_selection() {
+        async fn noop(_user: &GooseUser) {}
+
+        let api = GooseTaskSet::new("Api")
+            .set_tag("api")
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_on_start())
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_tag("slow"))
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_tag("checkout"));
+        let web = GooseTaskSet::new("Web")
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_on_start())
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))));
+
+        let mut configuration = GooseConfiguration::default();
+        assert!(is_task_set_selected(&configuration, &api));
+        assert!(is_task_set_selected(&configuration, &web));
+
+        // Tasks inherit task set tags, on start tasks don't need to match --tags.
+        configuration.tags = "checkout".to_string();
+        assert!(is_task_set_selected(&configuration, &api));
+        assert!(!is_task_set_selected(&configuration, &web));
+        let selected: Vec<bool> = api
+            .tasks
+            .iter()
+            .map(|t| is_task_selected(&configuration, &api, t))
+            .collect();
+        assert_eq!(selected, vec![true, false, true]);
+        configuration.tags = "api".to_string();
+        assert_eq!(weight_tasks(&api, &configuration).1, vec![vec![1, 2]]);
+
+        configuration.tags = "".to_string();
+        configuration.exclude_tags = "slow, checkout".to_string();
+        assert_eq!(
+            weight_tasks(&api, &configuration).1,
+            Vec::<Vec<usize>>::new()
+        );
+        configuration.exclude_tags = "api".to_string();
+        assert!(!is_task_set_selected(&configuration, &api));
+
+        configuration.exclude_tags = "".to_string();
+        configuration.tasksets = "Web".to_string();
+        assert!(!is_task_set_selected(&configuration, &api));
+        assert!(is_task_set_selected(&configuration, &web));
+    }
+
+    #[test]
+    fn weight_parsing() {
+        assert_eq!(parse_weight("Checkout=3"), Ok(("Checkout", 3)));
+        assert_eq!(parse_weight("Api.GET a=b=2"), Ok(("Api.GET a=b", 2)));
+        assert!(parse_weight("Checkout").is_err());
+        assert!(parse_weight("Checkout=0").is_err());
+        assert!(parse_weight("Checkout=many").is_err());
+    }
+
+    #[test]
+    fn weight_overrides() {
+        async fn noop(_user: &GooseUser) {}
+
+        let configuration = GooseConfiguration {
+            taskset_weight: vec!["Web.Site=4".to_string()],
+            task_weight: vec!["Web.Site.search=5".to_string()],
+            ..Default::default()
+        };
+        let mut goose_attack = GooseAttack::initialize_with_config(configuration)
+            .register_taskset(
+                GooseTaskSet::new("Web")
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_name("search")),
+            )
+            .register_taskset(
+                GooseTaskSet::new("Web.Site")
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_name("search")),
+            );
+        goose_attack.apply_weights();
+        assert_eq!(goose_attack.task_sets[0].weight, 1);
+        assert_eq!(goose_attack.task_sets[0].tasks[0].weight, 1);
+        assert_eq!(goose_attack.task_sets[1].weight, 4);
+        assert_eq!(goose_attack.task_sets[1].tasks[0].weight, 5);
+    }
+
+    #[test]
+    fn fixed_and_minimum_users() {
+        async fn noop(_user: &GooseUser) {}
+
+        let mut goose_attack = GooseAttack::initialize_with_config(GooseConfiguration::default())
+            .set_host("http://127.0.0.1")
+            .register_taskset(
+                GooseTaskSet::new("Admin")
+                    .set_fixed_users(1)
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u)))),
+            )
+            .register_taskset(
+                GooseTaskSet::new("Anonymous")
+                    .set_weight(10)
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u)))),
+            )
+            .register_taskset(
+                GooseTaskSet::new("Rare")
+                    .set_min_users(1)
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u)))),
+            );
+        goose_attack.users = 5;
+        let users: Vec<usize> = goose_attack
+            .weight_task_set_users()
+            .iter()
+            .map(|u| u.task_sets_index)
+            .collect();
+        // Required users come first, the rest are distributed by weight.
+        assert_eq!(users, vec![0, 2, 1, 1, 1]);
+
+        goose_attack.users = 14;
+        let users = goose_attack.weight_task_set_users();
+        assert_eq!(users.iter().filter(|u| u.task_sets_index == 0).count(), 1);
+        assert_eq!(users.iter().filter(|u| u.task_sets_index == 1).count(), 11);
+        assert_eq!(users.iter().filter(|u| u.task_sets_index == 2).count(), 2);
+    }
+
+    #[test]
+    fn load_test_hash_selection() {
+        async fn noop(_user: &GooseUser) {}
+
+        let task_sets = vec![GooseTaskSet::new("Api")
+            .set_tag("api")
+            .register_task(GooseTask::new(|u| Box::pin(noop(u))))];
+        let mut configuration = GooseConfiguration::default();
+        let hash = load_test_hash(&task_sets, &configuration);
+        assert_eq!(hash, load_test_hash(&task_sets.clone(), &configuration));
+        configuration.tags = "api".to_string();
+        assert_ne!(hash, load_test_hash(&task_sets, &configuration));
+    }
+
+    #[test]
+    fn list_json_hash() {
+        async fn noop(_user: &GooseUser) {}
+
+        let configuration = GooseConfiguration::from_iter(vec!["goose", "--tags", "api"]);
+        let mut goose_attack = GooseAttack::initialize_with_config(configuration)
+            .register_taskset(
+                GooseTaskSet::new("Api")
+                    .set_tag("api")
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_weight(2))
+                    .register_task(GooseTask::new(|u| Box::pin(noop(u))).set_on_start()),
+            )
+            .register_taskset(
+                GooseTaskSet::new("Web").register_task(GooseTask::new(|u| Box::pin(noop(u)))),
+            );
+        let list = goose_attack.list_json();
+        assert_eq!(list["task_sets"].as_array().unwrap().len(), 1);
+        assert_eq!(list["task_sets"][0]["weighted_tasks"], json!([[0, 0]]));
+
+        // The listed hash is the hash the load test runs with, after weighting tasks.
+        set_weighted_tasks(&mut goose_attack.task_sets, &goose_attack.configuration);
+        goose_attack.task_sets_hash =
+            load_test_hash(&goose_attack.task_sets, &goose_attack.configuration);
+        assert_eq!(
+            list["load_test_hash"],
+            goose_attack.task_sets_hash.to_string()
+        );
+    }
+
+    #[test]
+    fn default_options() {
+        assert_eq!(GooseDefault::HatchRate.option(), "hatch_rate");
+        assert_eq!(GooseDefault::ManagerBindPort.option(), "manager_bind_port");
+
+        let configuration = GooseConfiguration::from_iter(vec!["goose", "--hatch-rate", "4"]);
+        let goose_attack = GooseAttack::initialize_with_config(configuration)
+            .set_default(GooseDefault::Users, 5)
+            .set_default(GooseDefault::HatchRate, 10)
+            .set_default(GooseDefault::Host, "http://localhost")
+            .set_default(GooseDefault::NamedHost, "api=http://api")
+            .set_default(GooseDefault::NamedHost, "cdn=http://cdn")
+            .set_default(GooseDefault::NoStats, true);
+        assert_eq!(goose_attack.configuration.users, Some(5));
+        // Configured options aren't overridden.
+        assert_eq!(goose_attack.configuration.hatch_rate, 4);
+        assert_eq!(goose_attack.configuration.host, "http://localhost");
+        assert_eq!(
+            goose_attack.configuration.named_host,
+            vec!["api=http://api", "cdn=http://cdn"]
+        );
+        assert!(goose_attack.configuration.no_stats);
+    }
+
+    #[test]
+    fn request_name_cap() {
+        let configuration = GooseConfiguration {
+            max_request_names: 2,
+            ..Default::default()
+        };
+        let mut goose_attack = GooseAttack::initialize_with_config(configuration);
+        for key in &["GET /a", "GET /b"] {
+            assert!(!goose_attack.is_request_name_capped(key));
+            goose_attack.merged_requests.insert(
+                key.to_string(),
+                GooseRequest::new(&key[4..], goose::GooseMethod::GET, 0),
+            );
+        }
+        // Known request names are still tracked, new ones are counted as "other".
+        assert!(!goose_attack.is_request_name_capped("GET /a"));
+        assert!(goose_attack.is_request_name_capped("GET /c"));
+        assert!(goose_attack.request_names_capped);
+
+        // A limit of 0 disables the cap.
+        goose_attack.configuration.max_request_names = 0;
+        assert!(!goose_attack.is_request_name_capped("GET /c"));
+    }
 }
@@ -6,7 +6,8 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::{thread, time};
 
-use crate::goose::GooseRequest;
+use crate::abort::GooseAbortMonitor;
+use crate::goose::{GooseRequest, GooseUser};
 use crate::stats;
 use crate::util;
 use crate::{GooseAttack, GooseConfiguration, GooseUserCommand};
@@ -19,6 +20,8 @@ const GRACEFUL_SHUTDOWN_TIMEOUT: usize = 30;
 pub struct GooseUserInitializer {
     /// An index into the internal `GooseTest.task_sets` vector, indicating which GooseTaskSet is running.
     pub task_sets_index: usize,
+    /// An index into the manager's `GooseTest.weighted_users` vector, numbering users across the gaggle.
+    pub weighted_users_index: usize,
     /// The base_url for this user thread.
     pub base_url: String,
     /// Minimum amount of time to sleep after running a task.
@@ -31,24 +34,37 @@ pub struct GooseUserInitializer {
     pub worker_id: usize,
 }
 
+/// Statistics pushed by a worker to the manager.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GooseWorkerMessage {
+    /// Request statistics collected since the worker last pushed statistics.
+    pub requests: HashMap<String, GooseRequest>,
+    /// Set once all of the worker's users have exited, in the last statistics it pushes.
+    pub finished: bool,
+    /// The iterations completed by the worker's users, by task set index, pushed once the
+    /// worker is finished.
+    pub iterations: HashMap<usize, usize>,
+}
+
 // Mutable singleton globally tracking how many workers are currently being managed.
 lazy_static! {
     static ref ACTIVE_WORKERS: AtomicUsize = AtomicUsize::new(0);
 }
 
-fn distribute_users(goose_attack: &GooseAttack) -> (usize, usize) {
-    let users_per_worker =
-        goose_attack.users / (goose_attack.configuration.expect_workers as usize);
-    let users_remainder = goose_attack.users % (goose_attack.configuration.expect_workers as usize);
-    if users_remainder > 0 {
-        info!(
-            "each worker to start {} users, assigning 1 extra to {} workers",
-            users_per_worker, users_remainder
-        );
-    } else {
-        info!("each worker to start {} users", users_per_worker);
+/// Split users into one batch per worker round robin, numbering them by their index in
+/// the weighted users. Batches are popped as workers connect, so the last batch is for
+/// the first worker.
+fn assign_users(weighted_users: &[GooseUser], workers: usize) -> Vec<Vec<GooseUser>> {
+    let mut batches = vec![Vec::new(); workers];
+    for (index, user) in weighted_users.iter().enumerate() {
+        let mut user = user.clone();
+        user.weighted_users_index = index;
+        batches[index % workers].push(user);
     }
-    (users_per_worker, users_remainder)
+    let sizes: Vec<usize> = batches.iter().map(|batch| batch.len()).collect();
+    info!("users assigned to each worker: {:?}", sizes);
+    batches.reverse();
+    batches
 }
 
 fn pipe_closed(_pipe: Pipe, event: PipeEvent) {
@@ -65,6 +81,17 @@ fn pipe_closed(_pipe: Pipe, event: PipeEvent) {
     }
 }
 
+/// Merge the iterations completed by a finished worker's users into the iterations of
+/// the load test.
+fn merge_iterations(
+    iterations: &mut HashMap<usize, usize>,
+    worker_iterations: &HashMap<usize, usize>,
+) {
+    for (task_sets_index, count) in worker_iterations {
+        *iterations.entry(*task_sets_index).or_insert(0) += count;
+    }
+}
+
 /// Merge per-user-statistics from user thread into global parent statistics
 fn merge_from_worker(
     parent_request: &GooseRequest,
@@ -96,6 +123,41 @@ fn merge_from_worker(
     merged_request.success_count += &user_request.success_count;
     // Increment total fail counter.
     merged_request.fail_count += &user_request.fail_count;
+    // Increment byte counters.
+    merged_request.set_bytes(
+        user_request.request_bytes as u64,
+        user_request.response_bytes as u64,
+        user_request.response_decompressed_bytes as u64,
+    );
+    // Increment connection counters.
+    merged_request.connections_new += &user_request.connections_new;
+    merged_request.connections_reused += &user_request.connections_reused;
+    // Increment HTTP version counters.
+    for (http_version, count) in &user_request.http_version_counts {
+        let counter = match merged_request.http_version_counts.get(http_version) {
+            Some(c) => *c + count,
+            None => *count,
+        };
+        merged_request
+            .http_version_counts
+            .insert(http_version.to_string(), counter);
+    }
+    // Increment source address counters.
+    for (source_address, count) in &user_request.source_address_counts {
+        let counter = merged_request
+            .source_address_counts
+            .entry(source_address.to_string())
+            .or_insert(0);
+        *counter += count;
+    }
+    // Increment error counters.
+    for (error_kind, count) in &user_request.error_counts {
+        let counter = merged_request
+            .error_counts
+            .entry(error_kind.to_string())
+            .or_insert(0);
+        *counter += count;
+    }
     // Only accrue overhead of merging status_code_counts if we're going to display the results
     if config.status_codes {
         for (status_code, count) in &user_request.status_code_counts {
@@ -157,14 +219,17 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
         &address, goose_attack.configuration.expect_workers
     );
 
-    // Calculate how many users each worker will be responsible for.
-    let (users_per_worker, mut users_remainder) = distribute_users(&goose_attack);
-
-    // A mutable bucket of users to be assigned to workers.
-    let mut available_users = goose_attack.weighted_users.clone();
+    // Assign users to workers round robin, so each worker runs its share of the users of
+    // every task set, including task sets with fixed and minimum numbers of users.
+    let mut available_users = assign_users(
+        &goose_attack.weighted_users,
+        goose_attack.configuration.expect_workers as usize,
+    );
 
     // Track how many workers we've seen.
     let mut workers: HashSet<Pipe> = HashSet::new();
+    // Track which workers have finished, as all their users exited.
+    let mut finished_workers: HashSet<Pipe> = HashSet::new();
 
     // Track start time, we'll reset this when the test actually starts.
     let mut started = time::Instant::now();
@@ -177,12 +242,15 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
     let canceled = Arc::new(AtomicBool::new(false));
     util::setup_ctrlc_handler(&canceled);
 
+    // Evaluate abort conditions against statistics pushed by workers.
+    let mut abort_monitor = GooseAbortMonitor::new(&goose_attack.configuration);
+
     // Worker control loop.
     loop {
         // While running load test, check if any workers go away.
         if !load_test_finished {
-            // If ACTIVE_WORKERS is less than the total workers seen, a worker went away.
-            if ACTIVE_WORKERS.load(Ordering::SeqCst) < workers.len() {
+            // If ACTIVE_WORKERS is less than the total unfinished workers seen, a worker went away.
+            if ACTIVE_WORKERS.load(Ordering::SeqCst) + finished_workers.len() < workers.len() {
                 // If worked goes away during load test, exit gracefully.
                 if load_test_running {
                     info!(
@@ -209,6 +277,16 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                     load_test_finished = true;
                     exit_timer = time::Instant::now();
                 }
+                // Stop gracefully if an abort condition is met.
+                else if let Some(reason) = abort_monitor
+                    .as_mut()
+                    .and_then(|monitor| monitor.check(started.elapsed().as_secs() as usize))
+                {
+                    error!("aborting load test: {}", reason);
+                    goose_attack.aborted = Some(reason);
+                    load_test_finished = true;
+                    exit_timer = time::Instant::now();
+                }
             }
 
             // Aborting graceful shutdown, workers took too long to shut down.
@@ -217,13 +295,15 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                 break;
             }
 
-            // When displaying running statistics, sync data from user threads first.
-            if !goose_attack.configuration.only_summary
-                && util::timer_expired(running_statistics_timer, crate::RUNNING_STATS_EVERY)
-            {
-                // Reset timer each time we display statistics.
+            // Sample bandwidth over time, displaying running statistics if enabled.
+            if util::timer_expired(running_statistics_timer, crate::RUNNING_STATS_EVERY) {
+                // Reset timer each time we sample statistics.
                 running_statistics_timer = time::Instant::now();
-                stats::print_running_stats(&goose_attack, started.elapsed().as_secs() as usize);
+                let elapsed = started.elapsed().as_secs() as usize;
+                stats::record_bandwidth(&mut goose_attack, elapsed);
+                if !goose_attack.configuration.only_summary {
+                    stats::print_running_stats(&goose_attack, elapsed);
+                }
             }
         } else if canceled.load(Ordering::SeqCst) {
             info!("load test canceled, exiting");
@@ -242,9 +322,10 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                     }
                 };
 
-                // Workers always send a HashMap<String, GooseRequest>.
-                let requests: HashMap<String, GooseRequest> =
+                // Workers always send a GooseWorkerMessage.
+                let worker_message: GooseWorkerMessage =
                     serde_cbor::from_reader(msg.as_slice()).unwrap();
+                let requests = worker_message.requests;
                 debug!("requests statistics received: {:?}", requests.len());
 
                 // If workers already contains this pipe, we've seen this worker before.
@@ -252,11 +333,37 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                     let mut message = Message::new().unwrap();
                     // All workers are running load test, sending statistics.
                     if workers.len() == goose_attack.configuration.expect_workers as usize {
+                        // The worker's users have all exited, the load test is finished
+                        // once all workers are finished.
+                        if worker_message.finished {
+                            merge_iterations(
+                                &mut goose_attack.iterations,
+                                &worker_message.iterations,
+                            );
+                            finished_workers.insert(pipe);
+                            if finished_workers.len() == workers.len() && !load_test_finished {
+                                info!(
+                                    "all workers finished after {} seconds...",
+                                    started.elapsed().as_secs()
+                                );
+                                load_test_finished = true;
+                                exit_timer = time::Instant::now();
+                            }
+                        }
+                        if let Some(monitor) = abort_monitor.as_mut() {
+                            monitor
+                                .record_requests(&requests, started.elapsed().as_secs() as usize);
+                        }
                         // Requests statistics received, merge them into our local copy.
                         if !requests.is_empty() {
                             debug!("requests statistics received: {:?}", requests.len());
-                            for (request_key, request) in requests {
+                            for (mut request_key, mut request) in requests {
                                 trace!("request_key: {}", request_key);
+                                if goose_attack.is_request_name_capped(&request_key) {
+                                    request_key = format!("{:?} other", request.method);
+                                    request.path = "other".to_string();
+                                    request.host = "".to_string();
+                                }
                                 let merged_request;
                                 if let Some(parent_request) =
                                     goose_attack.merged_requests.get(&request_key)
@@ -389,27 +496,22 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
                             goose_attack.configuration.expect_workers
                         );
 
-                        // Send new worker a batch of users.
-                        let mut user_batch = users_per_worker;
-                        // If remainder, put extra user in this batch.
-                        if users_remainder > 0 {
-                            users_remainder -= 1;
-                            user_batch += 1;
-                        }
+                        // Send new worker its batch of users.
+                        let user_batch = match available_users.pop() {
+                            Some(u) => u,
+                            None => {
+                                error!("not enough available users!?");
+                                std::process::exit(1);
+                            }
+                        };
                         let mut users = Vec::new();
 
-                        // Pop users from available_users vector and build worker initializer.
-                        for _ in 1..=user_batch {
-                            let user = match available_users.pop() {
-                                Some(u) => u,
-                                None => {
-                                    error!("not enough available users!?");
-                                    std::process::exit(1);
-                                }
-                            };
+                        // Build worker initializers from the batch of users.
+                        for user in user_batch {
                             // Build a vector of GooseUser initializers for next worker.
                             users.push(GooseUserInitializer {
                                 task_sets_index: user.task_sets_index,
+                                weighted_users_index: user.weighted_users_index,
                                 base_url: user.base_url.read().await.to_string(),
                                 min_wait: user.min_wait,
                                 max_wait: user.max_wait,
@@ -471,34 +573,52 @@ pub async fn manager_main(mut goose_attack: GooseAttack) -> GooseAttack {
             }
         }
     }
+    // Sample the bandwidth used since the last running statistics.
+    stats::record_bandwidth(&mut goose_attack, started.elapsed().as_secs() as usize);
     goose_attack
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use url::Url;
+
+    #[test]
+    fn test_assign_users() {
+        let configuration = GooseConfiguration::default();
+        let base_url = Url::parse("http://127.0.0.1").unwrap();
+        let weighted_users: Vec<GooseUser> = (0..5)
+            .map(|index| GooseUser::new(index, base_url.clone(), 0, 0, &configuration, 0))
+            .collect();
+        let mut batches = assign_users(&weighted_users, 2);
+        let first_batch = batches.pop().unwrap();
+        let first: Vec<usize> = first_batch.iter().map(|u| u.task_sets_index).collect();
+        // Users are numbered by their index in the weighted users.
+        let first_indexes: Vec<usize> =
+            first_batch.iter().map(|u| u.weighted_users_index).collect();
+        let second: Vec<usize> = batches
+            .pop()
+            .unwrap()
+            .iter()
+            .map(|u| u.task_sets_index)
+            .collect();
+        assert_eq!(first, vec![0, 2, 4]);
+        assert_eq!(first_indexes, vec![0, 2, 4]);
+        assert_eq!(second, vec![1, 3]);
+        assert!(batches.is_empty());
+    }
 
     #[test]
-    fn test_distribute_users() {
-        let config = GooseConfiguration::default();
-        let mut goose_attack = GooseAttack::initialize_with_config(config);
-
-        goose_attack.users = 10;
-        goose_attack.configuration.expect_workers = 2;
-        let (users_per_process, users_remainder) = distribute_users(&goose_attack);
-        assert_eq!(users_per_process, 5);
-        assert_eq!(users_remainder, 0);
-
-        goose_attack.users = 1;
-        goose_attack.configuration.expect_workers = 1;
-        let (users_per_process, users_remainder) = distribute_users(&goose_attack);
-        assert_eq!(users_per_process, 1);
-        assert_eq!(users_remainder, 0);
-
-        goose_attack.users = 100;
-        goose_attack.configuration.expect_workers = 21;
-        let (users_per_process, users_remainder) = distribute_users(&goose_attack);
-        assert_eq!(users_per_process, 4);
-        assert_eq!(users_remainder, 16);
+    fn test_merge_iterations() {
+        let mut iterations = HashMap::new();
+        iterations.insert(0, 2);
+        let mut worker_iterations = HashMap::new();
+        worker_iterations.insert(0, 3);
+        worker_iterations.insert(1, 0);
+        merge_iterations(&mut iterations, &worker_iterations);
+        assert_eq!(iterations.get(&0), Some(&5));
+        assert_eq!(iterations.get(&1), Some(&0));
+        merge_iterations(&mut iterations, &worker_iterations);
+        assert_eq!(iterations.get(&0), Some(&8));
     }
 }
@@ -0,0 +1,533 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::{Position, Url};
+
+use crate::feeder;
+use crate::goose::{GooseMethod, GooseTask, GooseTaskSet, GooseUser};
+use crate::util;
+
+/// The name of the task set replaying requests.
+pub const REPLAY_TASK_SET: &str = "Replay";
+
+lazy_static! {
+    /// Matches an access log line in the Common or Combined Log Format.
+    static ref ACCESS_LOG_LINE: Regex =
+        Regex::new(r#"^(\S+) \S+ \S+ \[([^\]]+)\] "(\S+) (\S+)[^"]*""#).unwrap();
+    /// Matches an access log timestamp.
+    static ref ACCESS_LOG_TIME: Regex =
+        Regex::new(r"^(\d{2})/(\w{3})/(\d{4}):(\d{2}):(\d{2}):(\d{2}) ([+-])(\d{2})(\d{2})$")
+            .unwrap();
+}
+
+/// A request read from a stats log or access log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayRequest {
+    /// How many milliseconds after the first request in the log the request was made.
+    pub offset: u64,
+    /// The request method.
+    pub method: GooseMethod,
+    /// The path and query of the request, relative to `--host`.
+    pub path: String,
+    /// The request name from a stats log, None for access logs.
+    pub name: Option<String>,
+}
+
+/// The requests made by one user, in order.
+#[derive(Debug)]
+struct ReplayScript {
+    requests: Vec<ReplayRequest>,
+    /// The next request to replay.
+    position: AtomicUsize,
+}
+
+/// Requests read from a `--replay` log, shared by all replaying users.
+#[derive(Debug)]
+pub struct GooseReplay {
+    /// The requests of each user, by weighted user index.
+    scripts: Vec<ReplayScript>,
+    /// When the first user started replaying requests.
+    started: Mutex<Option<Instant>>,
+    /// Multiple of the original speed, None to replay as fast as possible.
+    speed: Option<f64>,
+    /// Set when the load test is stopping, so users stop waiting for their next request.
+    stopped: AtomicBool,
+}
+impl GooseReplay {
+    /// Read the requests to replay from the `--replay` file.
+    pub fn load(path: &str, speed: &str) -> Result<Self, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return Err(e.to_string()),
+        };
+        let users = parse_log(&contents)?;
+        if users.is_empty() {
+            return Err("no requests found".to_string());
+        }
+        Ok(GooseReplay {
+            scripts: users
+                .into_iter()
+                .map(|requests| ReplayScript {
+                    requests,
+                    position: AtomicUsize::new(0),
+                })
+                .collect(),
+            started: Mutex::new(None),
+            speed: parse_speed(speed),
+            stopped: AtomicBool::new(false),
+        })
+    }
+
+    /// How many users made requests in the log.
+    pub fn users(&self) -> usize {
+        self.scripts.len()
+    }
+
+    /// How many requests were read from the log.
+    pub fn requests(&self) -> usize {
+        self.scripts
+            .iter()
+            .map(|script| script.requests.len())
+            .sum()
+    }
+
+    /// Build the task set replaying the log, with one user for each user in the log.
+    /// Each iteration of the task set replays one request.
+    pub fn task_set(&self) -> GooseTaskSet {
+        let longest = self
+            .scripts
+            .iter()
+            .map(|script| script.requests.len())
+            .max()
+            .unwrap_or(1);
+        GooseTaskSet::new(REPLAY_TASK_SET)
+            .set_fixed_users(self.users())
+            .set_iterations(longest)
+            .register_task(GooseTask::new(move |s| Box::pin(replay_request(s))))
+    }
+
+    /// Wake users waiting for their next request, as the load test is stopping.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns when replaying started, starting now if this is the first request.
+    fn started(&self) -> Instant {
+        *self
+            .started
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now)
+    }
+}
+
+/// Parse `--replay-speed`, returning None to replay as fast as possible.
+pub fn parse_speed(speed: &str) -> Option<f64> {
+    if speed == "asap" {
+        None
+    } else {
+        speed.parse().ok()
+    }
+}
+
+/// Whether `--replay-speed` is "asap" or a positive multiple of the original speed.
+pub fn is_valid_speed(speed: &str) -> bool {
+    match speed.parse::<f64>() {
+        Ok(s) => s > 0.0 && s.is_finite(),
+        Err(_) => speed == "asap",
+    }
+}
+
+/// Replay the user's next request, once it is due.
+async fn replay_request(user: &GooseUser) {
+    let replay = match &user.replay {
+        Some(r) => r.clone(),
+        None => return,
+    };
+    let script = match replay.scripts.get(user.weighted_users_index) {
+        Some(s) => s,
+        None => return,
+    };
+    // Users with fewer requests than the longest script are done early.
+    let position = script.position.fetch_add(1, Ordering::SeqCst);
+    let request = match script.requests.get(position) {
+        Some(r) => r,
+        None => return,
+    };
+
+    if let Some(speed) = replay.speed {
+        let due =
+            replay.started() + Duration::from_secs_f64(request.offset as f64 / 1_000.0 / speed);
+        // Wait at most a second at a time, so stopping the load test isn't delayed.
+        loop {
+            if replay.stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            let now = Instant::now();
+            if now >= due {
+                break;
+            }
+            tokio::time::delay_for(std::cmp::min(due - now, Duration::from_secs(1))).await;
+        }
+    }
+
+    let request_builder = match request.method {
+        GooseMethod::DELETE => user.goose_delete(&request.path).await,
+        GooseMethod::GET => user.goose_get(&request.path).await,
+        GooseMethod::HEAD => user.goose_head(&request.path).await,
+        GooseMethod::PATCH => user.goose_patch(&request.path).await,
+        GooseMethod::POST => user.goose_post(&request.path).await,
+        GooseMethod::PUT => user.goose_put(&request.path).await,
+    };
+    let _response = user
+        .goose_send(request_builder, request.name.as_deref())
+        .await;
+}
+
+/// Returns the path and query of a logged url, relative to the host.
+fn parse_path(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(u) => u[Position::BeforePath..].to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// A logged request, and the user that made it.
+struct LoggedRequest {
+    user: String,
+    elapsed: u64,
+    method: GooseMethod,
+    path: String,
+    name: Option<String>,
+}
+
+/// Read the requests of each user from a `--stats-log-file` in json or csv format, or
+/// from an access log in the Common or Combined Log Format. Users are ordered by their
+/// first request, and each user's requests by when they were made.
+fn parse_log(contents: &str) -> Result<Vec<Vec<ReplayRequest>>, String> {
+    let first_line = contents
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    let logged = if first_line.starts_with('{') {
+        parse_stats_log_json(contents)?
+    } else if first_line.starts_with("elapsed,") {
+        parse_stats_log_csv(contents)?
+    } else {
+        parse_access_log(contents)?
+    };
+
+    let first = logged
+        .iter()
+        .map(|request| request.elapsed)
+        .min()
+        .unwrap_or(0);
+    let mut users: Vec<Vec<ReplayRequest>> = Vec::new();
+    let mut user_indexes = HashMap::new();
+    for request in logged {
+        let index = *user_indexes.entry(request.user).or_insert_with(|| {
+            users.push(Vec::new());
+            users.len() - 1
+        });
+        users[index].push(ReplayRequest {
+            offset: request.elapsed - first,
+            method: request.method,
+            path: request.path,
+            name: request.name,
+        });
+    }
+    for requests in &mut users {
+        requests.sort_by_key(|request| request.offset);
+    }
+    Ok(users)
+}
+
+/// Build a logged request from the fields of a stats log record, None if the record
+/// only updates an earlier request with `set_success` or `set_failure`.
+fn stats_log_request(
+    number: usize,
+    field: &dyn Fn(&str) -> Option<String>,
+) -> Result<Option<LoggedRequest>, String> {
+    if field("update").as_deref() == Some("true") {
+        return Ok(None);
+    }
+    let required = |name: &str| match field(name) {
+        Some(value) => Ok(value),
+        None => Err(format!("missing {} on line {}", name, number + 1)),
+    };
+    let elapsed = match required("elapsed")?.parse() {
+        Ok(e) => e,
+        Err(_) => return Err(format!("invalid elapsed on line {}", number + 1)),
+    };
+    let method = match required("method")?.parse() {
+        Ok(m) => m,
+        Err(_) => return Err(format!("unsupported method on line {}", number + 1)),
+    };
+    let name = required("name")?;
+    Ok(Some(LoggedRequest {
+        user: required("user")?,
+        elapsed,
+        method,
+        path: parse_path(&required("url")?),
+        name: if name.is_empty() { None } else { Some(name) },
+    }))
+}
+
+/// Parse a stats log written with `--stats-log-format json`.
+fn parse_stats_log_json(contents: &str) -> Result<Vec<LoggedRequest>, String> {
+    let mut logged = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = match serde_json::from_str(line) {
+            Ok(o) => o,
+            Err(e) => return Err(format!("invalid json on line {}: {}", number + 1, e)),
+        };
+        let field = |name: &str| match object.get(name) {
+            Some(serde_json::Value::String(s)) => Some(s.to_string()),
+            Some(value) => Some(value.to_string()),
+            None => None,
+        };
+        if let Some(request) = stats_log_request(number, &field)? {
+            logged.push(request);
+        }
+    }
+    Ok(logged)
+}
+
+/// Parse a stats log written with `--stats-log-format csv`.
+fn parse_stats_log_csv(contents: &str) -> Result<Vec<LoggedRequest>, String> {
+    let mut logged = Vec::new();
+    for (number, record) in feeder::parse_csv(contents)?.iter().enumerate() {
+        // Line numbers start after the header.
+        let field = |name: &str| record.get(name).cloned();
+        if let Some(request) = stats_log_request(number + 1, &field)? {
+            logged.push(request);
+        }
+    }
+    Ok(logged)
+}
+
+/// Parse an access log in the Common or Combined Log Format, such as:
+/// `127.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET /index.html HTTP/1.0" 200 2326`
+///
+/// Requests are grouped into users by client address. Lines that can't be parsed, or
+/// use unsupported methods, are skipped.
+fn parse_access_log(contents: &str) -> Result<Vec<LoggedRequest>, String> {
+    let mut logged = Vec::new();
+    let mut skipped = 0;
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = ACCESS_LOG_LINE.captures(line).and_then(|captures| {
+            Some(LoggedRequest {
+                user: captures[1].to_string(),
+                elapsed: parse_access_log_time(&captures[2])?,
+                method: captures[3].parse().ok()?,
+                path: parse_path(&captures[4]),
+                name: None,
+            })
+        });
+        match request {
+            Some(r) => logged.push(r),
+            None => skipped += 1,
+        }
+    }
+    if logged.is_empty() && skipped > 0 {
+        return Err("unrecognized log format".to_string());
+    }
+    if skipped > 0 {
+        warn!(
+            "skipped {} access log lines that couldn't be replayed",
+            skipped
+        );
+    }
+    Ok(logged)
+}
+
+/// Parse an access log timestamp such as `10/Oct/2000:13:55:36 -0700` into milliseconds
+/// since the Unix epoch.
+fn parse_access_log_time(time: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let captures = ACCESS_LOG_TIME.captures(time)?;
+    let number = |index: usize| captures[index].parse::<i64>().ok();
+    let day = number(1)?;
+    let month = MONTHS.iter().position(|m| *m == &captures[2])? as i64 + 1;
+    let year = number(3)?;
+
+    let days = util::days_from_civil(year, month, day);
+
+    let mut offset = number(8)? * 3_600 + number(9)? * 60;
+    if &captures[7] == "-" {
+        offset = -offset;
+    }
+    let seconds = days * 86_400 + number(4)? * 3_600 + number(5)? * 60 + number(6)? - offset;
+    if seconds < 0 {
+        None
+    } else {
+        Some(seconds as u64 * 1_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::goose::GooseRawRequest;
+    use crate::GooseAttack;
+
+    fn request(offset: u64, method: GooseMethod, path: &str, name: Option<&str>) -> ReplayRequest {
+        ReplayRequest {
+            offset,
+            method,
+            path: path.to_string(),
+            name: name.map(|n| n.to_string()),
+        }
+    }
+
+    #[test]
+    fn stats_log_json() {
+        let contents = r#"
+{"elapsed":1500,"method":"GET","name":"index","url":"http://127.0.0.1:8080/","final_url":"http://127.0.0.1:8080/","redirected":false,"response_time":2,"status_code":200,"success":true,"update":false,"user":1,"error":""}
+{"elapsed":1000,"method":"POST","name":"","url":"http://127.0.0.1:8080/login?next=%2F","final_url":"http://127.0.0.1:8080/","redirected":false,"response_time":2,"status_code":200,"success":true,"update":false,"user":0,"error":""}
+{"elapsed":1600,"method":"POST","name":"","url":"http://127.0.0.1:8080/login?next=%2F","final_url":"http://127.0.0.1:8080/","redirected":false,"response_time":2,"status_code":200,"success":false,"update":true,"user":0,"error":""}
+{"elapsed":1200,"method":"GET","name":"about","url":"http://127.0.0.1:8080/about","final_url":"http://127.0.0.1:8080/about","redirected":false,"response_time":2,"status_code":200,"success":true,"update":false,"user":1,"error":""}
+"#;
+        assert_eq!(
+            parse_log(contents).unwrap(),
+            vec![
+                // Users are ordered by their first request in the log.
+                vec![
+                    request(200, GooseMethod::GET, "/about", Some("about")),
+                    request(500, GooseMethod::GET, "/", Some("index")),
+                ],
+                // Updates from set_success and set_failure aren't replayed.
+                vec![request(0, GooseMethod::POST, "/login?next=%2F", None)],
+            ]
+        );
+    }
+
+    #[test]
+    fn stats_log_csv() {
+        let contents = "elapsed,method,name,url,final_url,redirected,response_time,status_code,success,update,user,request_bytes,response_bytes,response_decompressed_bytes,connection_reused_estimate,http_version,source_address,error_kind,error
+30,GET,\"index\",\"http://127.0.0.1:8080/\",\"http://127.0.0.1:8080/\",false,2,200,true,false,0,120,512,512,false,HTTP/1.1,,,\"\"
+10,GET,\"a, b\",\"http://127.0.0.1:8080/a\",\"http://127.0.0.1:8080/a\",false,2,200,true,false,1,120,512,512,true,HTTP/1.1,127.0.0.1,,\"\"
+";
+        assert_eq!(
+            parse_log(contents).unwrap(),
+            vec![
+                vec![request(20, GooseMethod::GET, "/", Some("index"))],
+                vec![request(0, GooseMethod::GET, "/a", Some("a, b"))],
+            ]
+        );
+
+        let missing = "elapsed,method,url\n10,GET,/\n";
+        assert_eq!(
+            parse_log(missing).unwrap_err(),
+            "missing name on line 2".to_string()
+        );
+    }
+
+    #[test]
+    fn stats_log_round_trip() {
+        let mut index = GooseRawRequest::new(
+            GooseMethod::GET,
+            "say \"hi\", twice",
+            "http://127.0.0.1:8080/?q=%22",
+            1_000,
+            0,
+        );
+        index.final_url = index.url.to_string();
+        let mut update = index.clone();
+        update.update = true;
+        update.success = false;
+        let mut login = GooseRawRequest::new(
+            GooseMethod::POST,
+            "",
+            "http://127.0.0.1:8080/login",
+            1_250,
+            1,
+        );
+        login.success = false;
+        login.error_kind = "connect".to_string();
+        login.error = "error trying to connect: \"refused\", retrying".to_string();
+        let raw_requests = [index, update, login];
+        let expected = vec![
+            vec![request(
+                0,
+                GooseMethod::GET,
+                "/?q=%22",
+                Some("say \"hi\", twice"),
+            )],
+            vec![request(250, GooseMethod::POST, "/login", None)],
+        ];
+
+        let mut header = true;
+        let csv: String = raw_requests
+            .iter()
+            .map(|raw_request| GooseAttack::prepare_csv(raw_request, &mut header) + "\n")
+            .collect();
+        assert_eq!(parse_log(&csv).unwrap(), expected);
+
+        let json: String = raw_requests
+            .iter()
+            .map(|raw_request| serde_json::json!(raw_request).to_string() + "\n")
+            .collect();
+        assert_eq!(parse_log(&json).unwrap(), expected);
+    }
+
+    #[test]
+    fn access_log() {
+        let contents = r#"10.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET /index.html HTTP/1.0" 200 2326
+10.0.0.2 - frank [10/Oct/2000:13:55:37 -0700] "POST /login HTTP/1.1" 302 - "http://example.com/" "Mozilla/5.0"
+10.0.0.1 - - [10/Oct/2000:13:55:39 -0700] "GET /about?page=2 HTTP/1.0" 200 512
+10.0.0.1 - - [10/Oct/2000:13:55:40 -0700] "OPTIONS * HTTP/1.0" 200 0
+not an access log line
+"#;
+        assert_eq!(
+            parse_log(contents).unwrap(),
+            vec![
+                vec![
+                    request(0, GooseMethod::GET, "/index.html", None),
+                    request(3_000, GooseMethod::GET, "/about?page=2", None),
+                ],
+                vec![request(1_000, GooseMethod::POST, "/login", None)],
+            ]
+        );
+        assert!(parse_log("not an access log line\n").is_err());
+    }
+
+    #[test]
+    fn access_log_time() {
+        assert_eq!(
+            parse_access_log_time("10/Oct/2000:13:55:36 -0700"),
+            Some(971_211_336_000)
+        );
+        assert_eq!(parse_access_log_time("01/Jan/1970:00:00:00 +0000"), Some(0));
+        assert_eq!(
+            parse_access_log_time("29/Feb/2024:23:00:00 +0100"),
+            Some(1_709_244_000_000)
+        );
+        assert!(parse_access_log_time("10/Foo/2000:13:55:36 -0700").is_none());
+    }
+
+    #[test]
+    fn speed() {
+        assert!(is_valid_speed("1"));
+        assert!(is_valid_speed("0.5"));
+        assert!(is_valid_speed("asap"));
+        assert!(!is_valid_speed("0"));
+        assert!(!is_valid_speed("-2"));
+        assert!(!is_valid_speed("fast"));
+        assert_eq!(parse_speed("2.5"), Some(2.5));
+        assert_eq!(parse_speed("asap"), None);
+    }
+}
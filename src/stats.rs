@@ -44,7 +44,7 @@ pub fn update_max_response_time(mut global_max: usize, max: usize) -> usize {
 }
 
 /// Get the response time that a certain number of percent of the requests finished within.
-fn calculate_response_time_percentile(
+pub(crate) fn calculate_response_time_percentile(
     response_times: &BTreeMap<usize, usize>,
     total_requests: usize,
     min: usize,
@@ -162,6 +162,71 @@ pub fn print_requests_and_fails(requests: &HashMap<String, GooseRequest>, elapse
     }
 }
 
+/// Merge requests together by named host, requests to the default host have an empty name.
+fn group_by_host(requests: &HashMap<String, GooseRequest>) -> BTreeMap<String, GooseRequest> {
+    let mut hosts: BTreeMap<String, GooseRequest> = BTreeMap::new();
+    for request in requests.values() {
+        let host = hosts
+            .entry(request.host.to_string())
+            .or_insert_with(|| GooseRequest::new("", request.method.clone(), 0));
+        host.response_times =
+            merge_response_times(host.response_times.clone(), request.response_times.clone());
+        host.total_response_time += request.total_response_time;
+        host.response_time_counter += request.response_time_counter;
+        host.min_response_time =
+            update_min_response_time(host.min_response_time, request.min_response_time);
+        host.max_response_time =
+            update_max_response_time(host.max_response_time, request.max_response_time);
+        host.success_count += request.success_count;
+        host.fail_count += request.fail_count;
+    }
+    hosts
+}
+
+/// Display a table of requests, fails and response times per named host.
+fn print_hosts(requests: &HashMap<String, GooseRequest>) {
+    debug!("entering print_hosts");
+    println!("-------------------------------------------------------------------------------");
+    println!(
+        " {:<23} | {:<14} | {:<14} | {:<8} | {:<8}",
+        "Host", "# reqs", "# fails", "Avg (ms)", "Median"
+    );
+    println!(" ----------------------------------------------------------------------------- ");
+    for (host, request) in group_by_host(requests) {
+        let name = if host.is_empty() { "(default)" } else { &host };
+        let response_time_counter = if request.response_time_counter == 0 {
+            1
+        } else {
+            request.response_time_counter
+        };
+        println!(
+            " {:<23} | {:<14} | {:<14} | {:<8} | {:<8}",
+            util::truncate_string(name, 23),
+            (request.success_count + request.fail_count).to_formatted_string(&Locale::en),
+            request.fail_count.to_formatted_string(&Locale::en),
+            request.total_response_time / response_time_counter,
+            util::median(
+                &request.response_times,
+                response_time_counter,
+                request.min_response_time,
+                request.max_response_time
+            ),
+        );
+    }
+}
+
+/// Only keep requests made against the `--stats-host` named host, if set.
+fn filter_by_host(
+    requests: &HashMap<String, GooseRequest>,
+    host: &str,
+) -> HashMap<String, GooseRequest> {
+    requests
+        .iter()
+        .filter(|(_, request)| host.is_empty() || request.host == host)
+        .map(|(request_key, request)| (request_key.to_string(), request.clone()))
+        .collect()
+}
+
 fn print_response_times(requests: &HashMap<String, GooseRequest>, display_percentiles: bool) {
     debug!("entering print_response_times");
     let mut aggregate_response_times: BTreeMap<usize, usize> = BTreeMap::new();
@@ -340,6 +405,295 @@ fn print_response_times(requests: &HashMap<String, GooseRequest>, display_percen
     }
 }
 
+/// Convert bytes to megabytes for display.
+fn bytes_to_mb(bytes: usize) -> f32 {
+    bytes as f32 / 1_048_576.0
+}
+
+/// Display a table of request and response sizes, and the bandwidth they used.
+fn print_bandwidth(requests: &HashMap<String, GooseRequest>, elapsed: usize) {
+    debug!("entering print_bandwidth");
+    // Avoid dividing by zero if statistics are displayed immediately.
+    let seconds = if elapsed > 0 { elapsed as f32 } else { 1.0 };
+    let mut aggregate_request_bytes: usize = 0;
+    let mut aggregate_response_bytes: usize = 0;
+    let mut aggregate_response_decompressed_bytes: usize = 0;
+    let mut aggregate_response_time_counter: usize = 0;
+    println!("-------------------------------------------------------------------------------");
+    println!(
+        " {:<23} | {:<7} | {:<7} | {:<7} | {:<9} | {:<7}",
+        "Name", "Avg (B)", "Sent MB", "Recv MB", "Decomp MB", "KB/s"
+    );
+    println!(" ----------------------------------------------------------------------------- ");
+    for (request_key, request) in requests.iter().sorted() {
+        aggregate_request_bytes += request.request_bytes;
+        aggregate_response_bytes += request.response_bytes;
+        aggregate_response_decompressed_bytes += request.response_decompressed_bytes;
+        aggregate_response_time_counter += request.response_time_counter;
+
+        let average_size = request
+            .response_bytes
+            .checked_div(request.response_time_counter)
+            .unwrap_or(0);
+        println!(
+            " {:<23} | {:<7} | {:<7.2} | {:<7.2} | {:<9.2} | {:<7.2}",
+            util::truncate_string(request_key, 23),
+            average_size.to_formatted_string(&Locale::en),
+            bytes_to_mb(request.request_bytes),
+            bytes_to_mb(request.response_bytes),
+            bytes_to_mb(request.response_decompressed_bytes),
+            (request.request_bytes + request.response_bytes) as f32 / 1024.0 / seconds,
+        );
+    }
+    if requests.len() > 1 {
+        println!(" ------------------------+---------+---------+---------+-----------+---------- ");
+        let average_size = aggregate_response_bytes
+            .checked_div(aggregate_response_time_counter)
+            .unwrap_or(0);
+        println!(
+            " {:<23} | {:<7} | {:<7.2} | {:<7.2} | {:<9.2} | {:<7.2}",
+            "Aggregated",
+            average_size.to_formatted_string(&Locale::en),
+            bytes_to_mb(aggregate_request_bytes),
+            bytes_to_mb(aggregate_response_bytes),
+            bytes_to_mb(aggregate_response_decompressed_bytes),
+            (aggregate_request_bytes + aggregate_response_bytes) as f32 / 1024.0 / seconds,
+        );
+    }
+}
+
+/// Total bytes sent and received by all requests, a number of seconds into the load
+/// test. Bandwidth over time is calculated from the difference between two samples.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct GooseBandwidthSample {
+    pub elapsed: usize,
+    pub request_bytes: usize,
+    pub response_bytes: usize,
+    pub response_decompressed_bytes: usize,
+}
+impl GooseBandwidthSample {
+    /// Sample the bytes sent and received by the requests so far.
+    pub fn new(requests: &HashMap<String, GooseRequest>, elapsed: usize) -> Self {
+        let mut sample = GooseBandwidthSample {
+            elapsed,
+            ..Default::default()
+        };
+        for request in requests.values() {
+            sample.request_bytes += request.request_bytes;
+            sample.response_bytes += request.response_bytes;
+            sample.response_decompressed_bytes += request.response_decompressed_bytes;
+        }
+        sample
+    }
+
+    /// Bandwidth in KB/s since the previous sample: sent, received, and received after
+    /// decompression.
+    fn kb_per_second(&self, previous: &GooseBandwidthSample) -> (f32, f32, f32) {
+        // Avoid dividing by zero if samples are taken immediately after each other.
+        let seconds = self.elapsed.saturating_sub(previous.elapsed).max(1) as f32;
+        let rate = |bytes: usize, previous_bytes: usize| {
+            bytes.saturating_sub(previous_bytes) as f32 / 1024.0 / seconds
+        };
+        (
+            rate(self.request_bytes, previous.request_bytes),
+            rate(self.response_bytes, previous.response_bytes),
+            rate(
+                self.response_decompressed_bytes,
+                previous.response_decompressed_bytes,
+            ),
+        )
+    }
+}
+
+/// Record the bytes sent and received so far, to display bandwidth over time.
+pub(crate) fn record_bandwidth(goose_attack: &mut GooseAttack, elapsed: usize) {
+    // Workers leave statistics to the manager.
+    if goose_attack.configuration.worker {
+        return;
+    }
+    let requests = filter_by_host(
+        &goose_attack.merged_requests,
+        &goose_attack.configuration.stats_host,
+    );
+    let sample = GooseBandwidthSample::new(&requests, elapsed);
+    goose_attack.bandwidth.push(sample);
+}
+
+/// Display a table of the bandwidth used between each pair of samples.
+fn print_bandwidth_over_time(samples: &[GooseBandwidthSample]) {
+    debug!("entering print_bandwidth_over_time");
+    println!("-------------------------------------------------------------------------------");
+    println!(
+        " {:<23} | {:<14} | {:<14} | {:<14}",
+        "Interval (s)", "Sent KB/s", "Recv KB/s", "Decomp KB/s"
+    );
+    println!(" ----------------------------------------------------------------------------- ");
+    for pair in samples.windows(2) {
+        let (sent, received, decompressed) = pair[1].kb_per_second(&pair[0]);
+        println!(
+            " {:<23} | {:<14.2} | {:<14.2} | {:<14.2}",
+            format!("{}-{}", pair[0].elapsed, pair[1].elapsed),
+            sent,
+            received,
+            decompressed,
+        );
+    }
+}
+
+/// Calculate what percentage of requests re-used an idle connection.
+fn reuse_percent(new: usize, reused: usize) -> f32 {
+    if new + reused > 0 {
+        reused as f32 / (new + reused) as f32 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Display a table of how many requests are estimated to have opened a new connection or
+/// re-used an idle one, see `GooseConnectionTracker`.
+fn print_connections(requests: &HashMap<String, GooseRequest>) {
+    debug!("entering print_connections");
+    let mut aggregate_new: usize = 0;
+    let mut aggregate_reused: usize = 0;
+    println!("-------------------------------------------------------------------------------");
+    println!(
+        " {:<23} | {:<14} | {:<14} | {:<19}",
+        "Name", "Est. new conn", "Est. reused", "Est. reuse %"
+    );
+    println!(" ----------------------------------------------------------------------------- ");
+    for (request_key, request) in requests.iter().sorted() {
+        aggregate_new += request.connections_new;
+        aggregate_reused += request.connections_reused;
+        println!(
+            " {:<23} | {:<14} | {:<14} | {:<19.2}",
+            util::truncate_string(request_key, 23),
+            request.connections_new.to_formatted_string(&Locale::en),
+            request.connections_reused.to_formatted_string(&Locale::en),
+            reuse_percent(request.connections_new, request.connections_reused),
+        );
+    }
+    if requests.len() > 1 {
+        println!(
+            " ------------------------+----------------+----------------+-------------------- "
+        );
+        println!(
+            " {:<23} | {:<14} | {:<14} | {:<19.2}",
+            "Aggregated",
+            aggregate_new.to_formatted_string(&Locale::en),
+            aggregate_reused.to_formatted_string(&Locale::en),
+            reuse_percent(aggregate_new, aggregate_reused),
+        );
+    }
+}
+
+/// Display a table of how often each HTTP version was used.
+fn print_http_versions(requests: &HashMap<String, GooseRequest>) {
+    debug!("entering print_http_versions");
+    println!("-------------------------------------------------------------------------------");
+    println!(" {:<23} | {:<25} ", "Name", "HTTP versions");
+    println!(" ----------------------------------------------------------------------------- ");
+    let mut aggregated_http_version_counts: HashMap<String, usize> = HashMap::new();
+    for (request_key, request) in requests.iter().sorted() {
+        let mut versions: Vec<String> = Vec::new();
+        for (http_version, count) in request.http_version_counts.iter().sorted() {
+            versions.push(format!(
+                "{} [{}]",
+                count.to_formatted_string(&Locale::en),
+                http_version
+            ));
+            let counter = aggregated_http_version_counts
+                .entry(http_version.to_string())
+                .or_insert(0);
+            *counter += count;
+        }
+        println!(
+            " {:<23} | {:<25}",
+            util::truncate_string(request_key, 23),
+            versions.join(", "),
+        );
+    }
+    println!(" ------------------------+------------------------------------------------------ ");
+    let mut versions: Vec<String> = Vec::new();
+    for (http_version, count) in aggregated_http_version_counts.iter().sorted() {
+        versions.push(format!(
+            "{} [{}]",
+            count.to_formatted_string(&Locale::en),
+            http_version
+        ));
+    }
+    println!(" {:<23} | {:<25} ", "Aggregated", versions.join(", "));
+}
+
+/// Display a table of how often each `--source-addresses` address was used.
+fn print_source_addresses(requests: &HashMap<String, GooseRequest>) {
+    debug!("entering print_source_addresses");
+    println!("-------------------------------------------------------------------------------");
+    println!(" {:<23} | {:<25} ", "Name", "Source addresses");
+    println!(" ----------------------------------------------------------------------------- ");
+    let mut aggregated_source_address_counts: HashMap<String, usize> = HashMap::new();
+    for (request_key, request) in requests.iter().sorted() {
+        let mut addresses: Vec<String> = Vec::new();
+        for (source_address, count) in request.source_address_counts.iter().sorted() {
+            addresses.push(format!(
+                "{} [{}]",
+                count.to_formatted_string(&Locale::en),
+                source_address
+            ));
+            let counter = aggregated_source_address_counts
+                .entry(source_address.to_string())
+                .or_insert(0);
+            *counter += count;
+        }
+        println!(
+            " {:<23} | {:<25}",
+            util::truncate_string(request_key, 23),
+            addresses.join(", "),
+        );
+    }
+    println!(" ------------------------+------------------------------------------------------ ");
+    let mut addresses: Vec<String> = Vec::new();
+    for (source_address, count) in aggregated_source_address_counts.iter().sorted() {
+        addresses.push(format!(
+            "{} [{}]",
+            count.to_formatted_string(&Locale::en),
+            source_address
+        ));
+    }
+    println!(" {:<23} | {:<25} ", "Aggregated", addresses.join(", "));
+}
+
+/// Display a table of failed requests, grouped by request and error kind.
+fn print_errors(requests: &HashMap<String, GooseRequest>) {
+    debug!("entering print_errors");
+    println!("-------------------------------------------------------------------------------");
+    println!(" {:<23} | {:<25} | {:<14}", "Name", "Error", "# errors");
+    println!(" ----------------------------------------------------------------------------- ");
+    let mut aggregated_error_counts: HashMap<String, usize> = HashMap::new();
+    for (request_key, request) in requests.iter().sorted() {
+        for (error_kind, count) in request.error_counts.iter().sorted() {
+            println!(
+                " {:<23} | {:<25} | {:<14}",
+                util::truncate_string(request_key, 23),
+                error_kind,
+                count.to_formatted_string(&Locale::en),
+            );
+            let counter = aggregated_error_counts
+                .entry(error_kind.to_string())
+                .or_insert(0);
+            *counter += count;
+        }
+    }
+    println!(" ------------------------+---------------------------+------------------------- ");
+    for (error_kind, count) in aggregated_error_counts.iter().sorted() {
+        println!(
+            " {:<23} | {:<25} | {:<14}",
+            "Aggregated",
+            error_kind,
+            count.to_formatted_string(&Locale::en),
+        );
+    }
+}
+
 fn print_status_codes(requests: &HashMap<String, GooseRequest>) {
     debug!("entering print_status_codes");
     println!("-------------------------------------------------------------------------------");
@@ -375,7 +729,7 @@ fn print_status_codes(requests: &HashMap<String, GooseRequest>) {
         }
         println!(
             " {:<23} | {:<25}",
-            util::truncate_string(&request_key, 23),
+            util::truncate_string(request_key, 23),
             codes,
         );
     }
@@ -400,28 +754,127 @@ fn print_status_codes(requests: &HashMap<String, GooseRequest>) {
     println!(" {:<23} | {:<25} ", "Aggregated", codes);
 }
 
+/// Display how many times users completed each task set.
+fn print_iterations(goose_attack: &GooseAttack) {
+    debug!("entering print_iterations");
+    println!("-------------------------------------------------------------------------------");
+    println!(
+        " {:<23} | {:<14} | {:<14}",
+        "Task set", "# iterations", "Limit"
+    );
+    println!(" ----------------------------------------------------------------------------- ");
+    let mut aggregated_iterations = 0;
+    for task_set in &goose_attack.task_sets {
+        if let Some(iterations) = goose_attack.iterations.get(&task_set.task_sets_index) {
+            // The --iterations option overrides the iterations of the task set.
+            let limit = if goose_attack.configuration.iterations > 0 {
+                goose_attack.configuration.iterations.to_string()
+            } else if let Some(limit) = task_set.iterations {
+                limit.to_string()
+            } else {
+                "none".to_string()
+            };
+            println!(
+                " {:<23} | {:<14} | {:<14}",
+                util::truncate_string(&task_set.name, 23),
+                iterations.to_formatted_string(&Locale::en),
+                limit,
+            );
+            aggregated_iterations += iterations;
+        }
+    }
+    println!(" ------------------------+----------------+----------------------------------- ");
+    println!(
+        " {:<23} | {:<14} |",
+        "Aggregated",
+        aggregated_iterations.to_formatted_string(&Locale::en),
+    );
+}
+
 /// Display running and ending statistics
 pub fn print_final_stats(goose_attack: &GooseAttack, elapsed: usize) {
     if !goose_attack.configuration.worker {
         info!("printing final statistics after {} seconds...", elapsed);
+        let requests = filter_by_host(
+            &goose_attack.merged_requests,
+            &goose_attack.configuration.stats_host,
+        );
         // 1) print request and fail statistics.
-        print_requests_and_fails(&goose_attack.merged_requests, elapsed);
-        // 2) print respones time statistics, with percentiles
-        print_response_times(&goose_attack.merged_requests, true);
-        // 3) print status_codes
+        print_requests_and_fails(&requests, elapsed);
+        // 2) print per-host statistics if requests were made against named hosts
+        if requests.values().any(|request| !request.host.is_empty()) {
+            print_hosts(&requests);
+        }
+        // 3) print respones time statistics, with percentiles
+        print_response_times(&requests, true);
+        // 4) print request and response sizes and bandwidth, overall and over time if
+        // bandwidth was sampled more than once
+        print_bandwidth(&requests, elapsed);
+        if goose_attack.bandwidth.len() > 1 {
+            print_bandwidth_over_time(&goose_attack.bandwidth);
+        }
+        // 5) print new and re-used connections if any were counted
+        if requests
+            .values()
+            .any(|request| request.connections_new + request.connections_reused > 0)
+        {
+            print_connections(&requests);
+        }
+        // 6) print HTTP versions if any responses were received
+        if requests
+            .values()
+            .any(|request| !request.http_version_counts.is_empty())
+        {
+            print_http_versions(&requests);
+        }
+        // 7) print source addresses if users are bound to them
+        if requests
+            .values()
+            .any(|request| !request.source_address_counts.is_empty())
+        {
+            print_source_addresses(&requests);
+        }
+        // 8) print status_codes
         if goose_attack.configuration.status_codes {
-            print_status_codes(&goose_attack.merged_requests);
+            print_status_codes(&requests);
+        }
+        // 9) print errors if any requests failed
+        if requests
+            .values()
+            .any(|request| !request.error_counts.is_empty())
+        {
+            print_errors(&requests);
+        }
+        // 10) print iterations if users run their task sets a limited number of times
+        if goose_attack.configuration.iterations > 0
+            || goose_attack
+                .task_sets
+                .iter()
+                .any(|task_set| task_set.iterations.is_some())
+        {
+            print_iterations(goose_attack);
         }
     }
 }
 
 pub fn print_running_stats(goose_attack: &GooseAttack, elapsed: usize) {
-    if !goose_attack.configuration.worker && !goose_attack.merged_requests.is_empty() {
+    let requests = filter_by_host(
+        &goose_attack.merged_requests,
+        &goose_attack.configuration.stats_host,
+    );
+    if !goose_attack.configuration.worker && !requests.is_empty() {
         info!("printing running statistics after {} seconds...", elapsed);
         // 1) print request and fail statistics.
-        print_requests_and_fails(&goose_attack.merged_requests, elapsed);
+        print_requests_and_fails(&requests, elapsed);
         // 2) print respones time statistics, without percentiles
-        print_response_times(&goose_attack.merged_requests, false);
+        print_response_times(&requests, false);
+        // 3) print request and response sizes and bandwidth, overall and since the
+        // previous running statistics
+        print_bandwidth(&requests, elapsed);
+        let samples = &goose_attack.bandwidth;
+        if samples.len() > 1 {
+            print_bandwidth_over_time(&samples[samples.len() - 2..]);
+        }
         println!();
     }
 }
@@ -430,6 +883,76 @@ pub fn print_running_stats(goose_attack: &GooseAttack, elapsed: usize) {
 mod test {
     use super::*;
 
+    #[test]
+    fn connection_reuse() {
+        assert_eq!(reuse_percent(0, 0), 0.0);
+        assert_eq!(reuse_percent(1, 0), 0.0);
+        assert_eq!(reuse_percent(1, 3), 75.0);
+        assert_eq!(reuse_percent(0, 5), 100.0);
+    }
+
+    #[test]
+    fn bandwidth_over_time() {
+        use crate::goose::GooseMethod;
+
+        let mut requests = HashMap::new();
+        let mut index = GooseRequest::new("/", GooseMethod::GET, 0);
+        index.set_bytes(1024, 4096, 8192);
+        requests.insert("GET /".to_string(), index);
+        let first = GooseBandwidthSample::new(&requests, 2);
+        assert_eq!(first.request_bytes, 1024);
+        assert_eq!(first.response_bytes, 4096);
+        assert_eq!(first.response_decompressed_bytes, 8192);
+        assert_eq!(
+            first.kb_per_second(&GooseBandwidthSample::default()),
+            (0.5, 2.0, 4.0)
+        );
+
+        requests
+            .get_mut("GET /")
+            .unwrap()
+            .set_bytes(0, 10240, 10240);
+        let second = GooseBandwidthSample::new(&requests, 7);
+        assert_eq!(second.kb_per_second(&first), (0.0, 2.0, 2.0));
+        // Samples taken in the same second don't divide by zero.
+        assert_eq!(second.kb_per_second(&second), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn hosts() {
+        use crate::goose::GooseMethod;
+
+        let mut requests = HashMap::new();
+        let mut index = GooseRequest::new("/", GooseMethod::GET, 0);
+        index.set_response_time(10);
+        index.success_count = 1;
+        requests.insert("GET /".to_string(), index);
+        for (key, response_time) in &[("GET api:/a", 20), ("GET api:/b", 40)] {
+            let mut request = GooseRequest::new(&key[8..], GooseMethod::GET, 0);
+            request.host = "api".to_string();
+            request.set_response_time(*response_time);
+            request.fail_count = 1;
+            requests.insert(key.to_string(), request);
+        }
+
+        // Requests are grouped by named host.
+        let hosts = group_by_host(&requests);
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[""].success_count, 1);
+        assert_eq!(hosts[""].total_response_time, 10);
+        assert_eq!(hosts["api"].fail_count, 2);
+        assert_eq!(hosts["api"].response_time_counter, 2);
+        assert_eq!(hosts["api"].min_response_time, 20);
+        assert_eq!(hosts["api"].max_response_time, 40);
+
+        // Requests can be filtered by named host.
+        assert_eq!(filter_by_host(&requests, "").len(), 3);
+        let filtered = filter_by_host(&requests, "api");
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.contains_key("GET api:/a"));
+        assert_eq!(filter_by_host(&requests, "cdn").len(), 0);
+    }
+
     #[test]
     fn max_response_time() {
         let mut max_response_time = 99;
@@ -455,6 +978,13 @@ mod test {
         assert_eq!(min_response_time, 9);
     }
 
+    #[test]
+    fn megabytes() {
+        assert_eq!(bytes_to_mb(0), 0.0);
+        assert_eq!(bytes_to_mb(1_048_576), 1.0);
+        assert_eq!(bytes_to_mb(524_288), 0.5);
+    }
+
     #[test]
     fn response_time_merge() {
         let mut global_response_times: BTreeMap<usize, usize> = BTreeMap::new();
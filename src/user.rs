@@ -1,5 +1,4 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rand::Rng;
 use std::sync::atomic::Ordering;
 use std::time;
@@ -14,7 +13,7 @@ pub async fn user_main(
     mut thread_user: GooseUser,
     mut thread_receiver: mpsc::UnboundedReceiver<GooseUserCommand>,
     worker: bool,
-) {
+) -> usize {
     if worker {
         info!(
             "[{}] launching user {} from {}...",
@@ -29,11 +28,15 @@ pub async fn user_main(
         );
     }
 
+    // Shuffle tasks and pick wait times with the user's own, optionally seeded, random
+    // number generator.
+    let rng = thread_user.rng.clone();
+
     // User is starting, first invoke the weighted on_start tasks.
     if !thread_user.weighted_on_start_tasks.is_empty() {
         for mut sequence in thread_user.weighted_on_start_tasks.clone() {
             if sequence.len() > 1 {
-                sequence.shuffle(&mut thread_rng());
+                sequence.shuffle(&mut *rng.lock().unwrap());
             }
             for task_index in &sequence {
                 // Determine which task we're going to run next.
@@ -46,12 +49,21 @@ pub async fn user_main(
                 if thread_task_name != "" {
                     thread_user.task_request_name = Some(thread_task_name.to_string());
                 }
+                thread_user.tasks_index = *task_index;
                 // Invoke the task function.
                 function(&thread_user).await;
             }
         }
     }
 
+    // The --iterations option overrides how many times the task set runs.
+    let max_iterations = if thread_user.config.iterations > 0 {
+        thread_user.config.iterations
+    } else {
+        thread_task_set.iterations.unwrap_or(0)
+    };
+    let mut iterations: usize = 0;
+
     // Repeatedly loop through all available tasks in a random order.
     let mut thread_continue: bool = true;
     let mut weighted_bucket = thread_user.weighted_bucket.load(Ordering::SeqCst);
@@ -77,7 +89,7 @@ pub async fn user_main(
                 .weighted_bucket
                 .store(weighted_bucket_position, Ordering::SeqCst);
             // Shuffle new bucket before we walk through the tasks.
-            thread_user.weighted_tasks[weighted_bucket].shuffle(&mut thread_rng());
+            thread_user.weighted_tasks[weighted_bucket].shuffle(&mut *rng.lock().unwrap());
             debug!(
                 "re-shuffled {} tasks: {:?}",
                 &thread_task_set.name, thread_user.weighted_tasks[weighted_bucket]
@@ -97,12 +109,29 @@ pub async fn user_main(
         if thread_task_name != "" {
             thread_user.task_request_name = Some(thread_task_name.to_string());
         }
+        thread_user.tasks_index = thread_weighted_task;
         // Invoke the task function.
         function(&thread_user).await;
 
+        // Running the last task of the last bucket completes an iteration.
+        if weighted_bucket_position + 1 >= thread_user.weighted_tasks[weighted_bucket].len()
+            && weighted_bucket + 1 >= thread_user.weighted_tasks.len()
+        {
+            iterations += 1;
+            if max_iterations > 0 && iterations >= max_iterations {
+                debug!(
+                    "user {} from {} completed {} iterations",
+                    thread_number, thread_task_set.name, iterations
+                );
+                thread_continue = false;
+            }
+        }
+
         // Prepare to sleep for a random value from min_wait to max_wait.
         let wait_time = if thread_user.max_wait > 0 {
-            rand::thread_rng().gen_range(thread_user.min_wait, thread_user.max_wait)
+            rng.lock()
+                .unwrap()
+                .gen_range(thread_user.min_wait, thread_user.max_wait)
         } else {
             0
         };
@@ -153,7 +182,7 @@ pub async fn user_main(
     if !thread_user.weighted_on_stop_tasks.is_empty() {
         for mut sequence in thread_user.weighted_on_stop_tasks.clone() {
             if sequence.len() > 1 {
-                sequence.shuffle(&mut thread_rng());
+                sequence.shuffle(&mut *rng.lock().unwrap());
             }
             for task_index in &sequence {
                 // Determine which task we're going to run next.
@@ -166,6 +195,7 @@ pub async fn user_main(
                 if thread_task_name != "" {
                     thread_user.task_request_name = Some(thread_task_name.to_string());
                 }
+                thread_user.tasks_index = *task_index;
                 // Invoke the task function.
                 function(&thread_user).await;
             }
@@ -186,4 +216,6 @@ pub async fn user_main(
             thread_number, thread_task_set.name
         );
     }
+
+    iterations
 }
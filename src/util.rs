@@ -104,6 +104,22 @@ pub fn timer_expired(started: time::Instant, run_time: usize) -> bool {
     run_time > 0 && started.elapsed().as_secs() >= run_time as u64
 }
 
+/// Calculate the number of days from the Unix epoch to a date in the proleptic
+/// Gregorian calendar, with months numbered from 1.
+/// More detail: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
 pub fn setup_ctrlc_handler(canceled: &Arc<AtomicBool>) {
     let caught_ctrlc = canceled.clone();
     match ctrlc::set_handler(move || {
@@ -240,6 +256,13 @@ mod tests {
         assert_eq!(truncate_string("abcde", 2), "..");
     }
 
+    #[test]
+    fn civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
     #[test]
     fn timer() {
         use std::thread;
@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::goose::{GooseDebug, GooseRawRequest, GooseTask, GooseTaskSet, GooseUser};
+use crate::{goose, is_task_selected, is_task_set_selected, GooseAttack};
+
+/// How many characters of a logged response body to include in the report.
+const MAX_BODY_LENGTH: usize = 500;
+
+/// The outcome of running one task once in `--validate` mode.
+#[derive(Debug)]
+struct ValidateResult {
+    /// Where the task ran: "test start", "on start", "task", "on stop" or "test stop".
+    stage: &'static str,
+    /// The name of the task set the task belongs to, empty for test start and stop tasks.
+    task_set: String,
+    /// The task index and name.
+    task: String,
+    /// The requests made by the task, updated by `set_success` and `set_failure`.
+    requests: Vec<GooseRawRequest>,
+    /// The debug details logged while the task was running.
+    debug: Vec<GooseDebug>,
+}
+impl ValidateResult {
+    /// Returns the first failed request made by the task, if any.
+    fn first_failure(&self) -> Option<&GooseRawRequest> {
+        self.requests.iter().find(|request| !request.success)
+    }
+}
+
+/// Add a request to the requests made by a task. Requests updated with `set_success`
+/// or `set_failure` replace the most recent matching request.
+fn record_request(requests: &mut Vec<GooseRawRequest>, request: GooseRawRequest) {
+    if request.update {
+        if let Some(previous) = requests
+            .iter_mut()
+            .rev()
+            .find(|previous| previous.method == request.method && previous.url == request.url)
+        {
+            *previous = request;
+            return;
+        }
+    }
+    requests.push(request);
+}
+
+/// Flatten weighted tasks into a list of unique task indexes, in the order they run.
+fn unique_tasks(weighted_tasks: &[Vec<usize>]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    weighted_tasks
+        .iter()
+        .flatten()
+        .filter(|task_index| seen.insert(**task_index))
+        .cloned()
+        .collect()
+}
+
+/// Run one task, collecting the requests it made and the debug details it logged.
+async fn run_task(
+    user: &mut GooseUser,
+    stage: &'static str,
+    task_set: &str,
+    task: &GooseTask,
+    parent_receiver: &mut mpsc::UnboundedReceiver<GooseRawRequest>,
+    logger_receiver: &mut mpsc::UnboundedReceiver<Option<GooseDebug>>,
+) -> ValidateResult {
+    debug!("validating {} {} task {}", task_set, stage, task.name);
+    user.tasks_index = task.tasks_index;
+    user.task_request_name = if task.name.is_empty() {
+        None
+    } else {
+        Some(task.name.to_string())
+    };
+    (task.function)(user).await;
+
+    let mut requests = Vec::new();
+    while let Ok(request) = parent_receiver.try_recv() {
+        record_request(&mut requests, request);
+    }
+    let mut debug = Vec::new();
+    while let Ok(message) = logger_receiver.try_recv() {
+        if let Some(goose_debug) = message {
+            debug.push(goose_debug);
+        }
+    }
+
+    ValidateResult {
+        stage,
+        task_set: task_set.to_string(),
+        task: format!("#{} {}", task.tasks_index, task.name)
+            .trim_end()
+            .to_string(),
+        requests,
+        debug,
+    }
+}
+
+/// Run the selected tasks of a task set once each with a single user: first the
+/// on_start tasks, then the other tasks and finally the on_stop tasks.
+async fn validate_task_set(
+    goose_attack: &GooseAttack,
+    task_set: &GooseTaskSet,
+    mut user: GooseUser,
+    parent_receiver: &mut mpsc::UnboundedReceiver<GooseRawRequest>,
+    logger_receiver: &mut mpsc::UnboundedReceiver<Option<GooseDebug>>,
+) -> Vec<ValidateResult> {
+    let stages = vec![
+        ("on start", &task_set.weighted_on_start_tasks),
+        ("task", &task_set.weighted_tasks),
+        ("on stop", &task_set.weighted_on_stop_tasks),
+    ];
+    let mut results = Vec::new();
+    let mut validated = HashSet::new();
+    for (stage, weighted_tasks) in stages {
+        for task_index in unique_tasks(weighted_tasks) {
+            let task = &task_set.tasks[task_index];
+            // Tasks that run both on start and on stop are only validated once.
+            if !is_task_selected(&goose_attack.configuration, task_set, task)
+                || !validated.insert(task_index)
+            {
+                continue;
+            }
+            results.push(
+                run_task(
+                    &mut user,
+                    stage,
+                    &task_set.name,
+                    task,
+                    parent_receiver,
+                    logger_receiver,
+                )
+                .await,
+            );
+        }
+    }
+    results
+}
+
+/// Print a report of all validated tasks, including details about the first failed
+/// request of each failed task. Returns true if all tasks passed.
+fn print_report(results: &[ValidateResult]) -> bool {
+    let mut failed = 0;
+    println!("Validated tasks:");
+    let mut task_set = None;
+    for result in results {
+        if task_set != Some(&result.task_set) && !result.task_set.is_empty() {
+            println!(" - {}", result.task_set);
+            task_set = Some(&result.task_set);
+        }
+        let requests = match result.requests.len() {
+            1 => "1 request".to_string(),
+            count => format!("{} requests", count),
+        };
+        match result.first_failure() {
+            None => println!(
+                "    PASS  {:<10} {} ({})",
+                result.stage, result.task, requests
+            ),
+            Some(request) => {
+                failed += 1;
+                println!(
+                    "    FAIL  {:<10} {} ({})",
+                    result.stage, result.task, requests
+                );
+                println!(
+                    "          first failed request: {:?} {} ({})",
+                    request.method, request.url, request.name
+                );
+                if !request.final_url.is_empty() && request.final_url != request.url {
+                    println!("          final url: {}", request.final_url);
+                }
+                println!(
+                    "          status code: {}, response time: {} ms",
+                    request.status_code, request.response_time
+                );
+                if request.error_kind.starts_with("status") {
+                    // The error of a status error already includes the status code.
+                    println!("          error: {}", request.error);
+                } else if !request.error_kind.is_empty() {
+                    println!("          error: {} {}", request.error_kind, request.error);
+                }
+                for goose_debug in &result.debug {
+                    println!("          debug: {}", goose_debug.tag);
+                    if let Some(header) = &goose_debug.header {
+                        println!("            headers: {}", header);
+                    }
+                    if let Some(body) = &goose_debug.body {
+                        if body.chars().count() > MAX_BODY_LENGTH {
+                            let body: String = body.chars().take(MAX_BODY_LENGTH).collect();
+                            println!("            body: {}...", body);
+                        } else {
+                            println!("            body: {}", body);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    println!(
+        "{} tasks validated: {} passed, {} failed",
+        results.len(),
+        results.len() - failed,
+        failed
+    );
+    failed == 0
+}
+
+/// Run every selected task once, with one GooseUser per task set, then print a report
+/// of which tasks passed. Returns true if all tasks passed.
+pub async fn validate_main(mut goose_attack: GooseAttack) -> bool {
+    info!("validating tasks");
+
+    // Capture the requests and debug details of each task instead of sending them to
+    // the parent and logger threads.
+    let (parent_sender, mut parent_receiver): (
+        mpsc::UnboundedSender<GooseRawRequest>,
+        mpsc::UnboundedReceiver<GooseRawRequest>,
+    ) = mpsc::unbounded_channel();
+    let (logger_sender, mut logger_receiver): (
+        mpsc::UnboundedSender<Option<GooseDebug>>,
+        mpsc::UnboundedReceiver<Option<GooseDebug>>,
+    ) = mpsc::unbounded_channel();
+
+    let feeders = Arc::new(std::mem::take(&mut goose_attack.feeders));
+    let single_user = |goose_attack: &GooseAttack| {
+        let base_url = goose::get_base_url(
+            goose_attack.get_configuration_host(),
+            None,
+            goose_attack.host.clone(),
+        );
+        let mut user = GooseUser::single(base_url, &goose_attack.configuration);
+        user.init_cookies(&goose_attack.cookies);
+        user.request_name_rules = Arc::clone(&goose_attack.request_name_rules);
+        user.parent = Some(parent_sender.clone());
+        user.logger = Some(logger_sender.clone());
+        user
+    };
+
+    let mut results = Vec::new();
+    if let Some(task) = &goose_attack.test_start_task {
+        let mut user = single_user(&goose_attack);
+        results.push(
+            run_task(
+                &mut user,
+                "test start",
+                "",
+                task,
+                &mut parent_receiver,
+                &mut logger_receiver,
+            )
+            .await,
+        );
+    }
+
+    for task_set in &goose_attack.task_sets {
+        if !is_task_set_selected(&goose_attack.configuration, task_set) {
+            continue;
+        }
+        let mut user = goose_attack.build_user(task_set);
+        user.feeders = feeders.clone();
+        user.options = goose_attack.options.clone();
+        user.parent = Some(parent_sender.clone());
+        user.logger = Some(logger_sender.clone());
+        results.append(
+            &mut validate_task_set(
+                &goose_attack,
+                task_set,
+                user,
+                &mut parent_receiver,
+                &mut logger_receiver,
+            )
+            .await,
+        );
+    }
+
+    if let Some(task) = &goose_attack.test_stop_task {
+        let mut user = single_user(&goose_attack);
+        results.push(
+            run_task(
+                &mut user,
+                "test stop",
+                "",
+                task,
+                &mut parent_receiver,
+                &mut logger_receiver,
+            )
+            .await,
+        );
+    }
+
+    print_report(&results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::goose::GooseMethod;
+
+    fn request(url: &str, success: bool, update: bool) -> GooseRawRequest {
+        let mut request = GooseRawRequest::new(GooseMethod::GET, "/", url, 0, 0);
+        request.success = success;
+        request.update = update;
+        request
+    }
+
+    #[test]
+    fn record_requests() {
+        let mut requests = Vec::new();
+        record_request(&mut requests, request("http://127.0.0.1/", true, false));
+        record_request(
+            &mut requests,
+            request("http://127.0.0.1/about", true, false),
+        );
+        // Marking a request as failed replaces the original request.
+        record_request(&mut requests, request("http://127.0.0.1/", false, true));
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].success);
+        assert!(requests[1].success);
+
+        let result = ValidateResult {
+            stage: "task",
+            task_set: "LoadTest".to_string(),
+            task: "#0 index".to_string(),
+            requests,
+            debug: Vec::new(),
+        };
+        assert_eq!(result.first_failure().unwrap().url, "http://127.0.0.1/");
+        assert!(!print_report(&[result]));
+    }
+
+    #[test]
+    fn unique_task_order() {
+        assert_eq!(unique_tasks(&[vec![1, 1, 0], vec![2, 0, 2]]), vec![1, 0, 2]);
+        assert!(unique_tasks(&[]).is_empty());
+    }
+}
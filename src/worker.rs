@@ -1,13 +1,16 @@
 use nng::*;
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::{thread, time};
 use url::Url;
 
-use crate::goose::{GooseMethod, GooseRequest, GooseUser, GooseUserCommand};
-use crate::manager::GooseUserInitializer;
+use crate::goose::{
+    self, GooseConnectionPool, GooseMethod, GooseRequest, GooseUser, GooseUserCommand,
+};
+use crate::manager::{GooseUserInitializer, GooseWorkerMessage};
 use crate::util;
-use crate::{get_worker_id, GooseAttack, GooseConfiguration, WORKER_ID};
+use crate::{cookies, get_worker_id, GooseAttack, GooseConfiguration, WORKER_ID};
 
 // If pipe closes unexpectedly, exit.
 fn pipe_closed(_pipe: Pipe, event: PipeEvent) {
@@ -83,10 +86,14 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
         "sending load test hash to manager: {}",
         goose_attack.task_sets_hash
     );
-    push_stats_to_manager(&manager, &requests, false);
-
-    // Only send load_test_hash one time.
-    requests = HashMap::new();
+    push_stats_to_manager(
+        &manager,
+        &GooseWorkerMessage {
+            requests,
+            ..Default::default()
+        },
+        false,
+    );
 
     let mut hatch_rate: Option<f32> = None;
     let mut config: GooseConfiguration = GooseConfiguration::default();
@@ -133,7 +140,7 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
             if worker_id == 0 {
                 worker_id = initializer.worker_id;
             }
-            let user = GooseUser::new(
+            let mut user = GooseUser::new(
                 initializer.task_sets_index,
                 Url::parse(&initializer.base_url).unwrap(),
                 initializer.min_wait,
@@ -141,6 +148,11 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
                 &initializer.config,
                 goose_attack.task_sets_hash,
             );
+            user.weighted_users_index = initializer.weighted_users_index;
+            user.init_auth(&goose_attack.task_sets[initializer.task_sets_index].auth);
+            user.har = goose_attack.task_sets[initializer.task_sets_index]
+                .har
+                .clone();
             weighted_users.push(user);
             if hatch_rate == None {
                 hatch_rate = Some(
@@ -155,6 +167,48 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
                 );
             }
         }
+        if config.connection_pool == GooseConnectionPool::Shared {
+            goose::share_client(&mut weighted_users);
+        }
+        // Load the cookies file once, each user gets its own cookie jar.
+        let seed = if config.cookies_file.is_empty() {
+            Vec::new()
+        } else {
+            match cookies::load_cookies_file(&config.cookies_file) {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Invalid --cookies-file: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        };
+        // Compile the request name rules once, they're shared by all users.
+        let request_name_rules = match goose::get_request_name_rules(&config.request_name_rule) {
+            Ok(r) => Arc::new(r),
+            Err(e) => {
+                error!("Invalid --request-name-rule: {}", e);
+                std::process::exit(1);
+            }
+        };
+        // Each worker gets its own share of unique feeder records.
+        let feeders = Arc::new(
+            goose_attack
+                .feeders
+                .iter()
+                .map(|(name, feeder)| {
+                    (
+                        name.to_string(),
+                        feeder.partition(worker_id, config.expect_workers as usize),
+                    )
+                })
+                .collect(),
+        );
+        for user in &mut weighted_users {
+            user.init_cookies(&seed);
+            user.request_name_rules = Arc::clone(&request_name_rules);
+            user.feeders = Arc::clone(&feeders);
+            user.options = goose_attack.options.clone();
+        }
         WORKER_ID.store(worker_id, Ordering::Relaxed);
         info!(
             "[{}] initialized {} user states",
@@ -169,7 +223,7 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
     // Wait for the manager to send go-ahead to start the load test.
     loop {
         // Push statistics to manager to force a reply, waiting for RUN.
-        push_stats_to_manager(&manager, &requests, false);
+        push_stats_to_manager(&manager, &GooseWorkerMessage::default(), false);
         let msg = match manager.recv() {
             Ok(m) => m,
             Err(e) => {
@@ -239,16 +293,16 @@ pub async fn worker_main(goose_attack: &GooseAttack) -> GooseAttack {
 
 pub fn push_stats_to_manager(
     manager: &Socket,
-    requests: &HashMap<String, GooseRequest>,
+    worker_message: &GooseWorkerMessage,
     get_response: bool,
 ) -> bool {
     debug!(
         "[{}] pushing stats to manager: {}",
         get_worker_id(),
-        requests.len()
+        worker_message.requests.len()
     );
     let mut message = Message::new().unwrap();
-    match serde_cbor::to_writer(&mut message, requests) {
+    match serde_cbor::to_writer(&mut message, worker_message) {
         Ok(_) => (),
         Err(e) => {
             error!(
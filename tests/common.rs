@@ -1,18 +1,39 @@
+use goose::goose::GooseConnectionPool;
 use goose::GooseConfiguration;
 
 pub fn build_configuration() -> GooseConfiguration {
     // Manually specify configuration for test, normally this is provided as
     // CLI options.
     GooseConfiguration {
+        config_file: "".to_string(),
         host: "http://127.0.0.1:5000".to_string(),
+        named_host: Vec::new(),
         users: Some(1),
         hatch_rate: 1,
         run_time: "1".to_string(),
+        iterations: 0,
+        seed: None,
+        replay: "".to_string(),
+        replay_speed: "1".to_string(),
+        abort_fail_rate: 0,
+        abort_p95: 0,
+        abort_transport_errors: 0,
+        abort_window: 60,
         no_stats: true,
         status_codes: false,
+        request_name_rule: Vec::new(),
+        max_request_names: 1000,
+        stats_host: "".to_string(),
         only_summary: false,
         reset_stats: false,
         list: false,
+        list_format: "text".to_string(),
+        validate: false,
+        tags: "".to_string(),
+        exclude_tags: "".to_string(),
+        tasksets: "".to_string(),
+        taskset_weight: Vec::new(),
+        task_weight: Vec::new(),
         verbose: 0,
         log_level: 0,
         log_file: "goose.log".to_string(),
@@ -21,6 +42,21 @@ pub fn build_configuration() -> GooseConfiguration {
         debug_log_file: "".to_string(),
         debug_log_format: "json".to_string(),
         sticky_follow: false,
+        no_keepalive: false,
+        max_idle_connections: None,
+        idle_timeout: None,
+        connection_pool: GooseConnectionPool::User,
+        http_version: None,
+        cookies_file: "".to_string(),
+        source_addresses: "".to_string(),
+        proxy: "".to_string(),
+        timeout: None,
+        connect_timeout: None,
+        user_agent: "".to_string(),
+        header: Vec::new(),
+        gzip: false,
+        max_redirects: None,
+        accept_invalid_certs: false,
         manager: false,
         no_hash_check: false,
         expect_workers: 0,
@@ -31,3 +67,13 @@ pub fn build_configuration() -> GooseConfiguration {
         manager_port: 5115,
     }
 }
+
+/// Returns a path in the system's temporary directory for a file written by a test,
+/// unique to the test process so tests running in parallel don't share files.
+#[allow(dead_code)]
+pub fn temp_file(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("goose-{}-{}", std::process::id(), name))
+        .to_string_lossy()
+        .to_string()
+}
@@ -0,0 +1,55 @@
+use httpmock::Method::GET;
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::feeder::{GooseFeeder, GooseFeederStrategy};
+use goose::prelude::*;
+
+const ALICE_PATH: &str = "/login/alice";
+const BOB_PATH: &str = "/login/bob";
+const CAROL_PATH: &str = "/login/carol";
+
+pub async fn login(user: &GooseUser) {
+    if let Some(record) = user.feed("users") {
+        let _response = user.get(&format!("/login/{}", record["username"])).await;
+    }
+}
+
+#[test]
+#[with_mock_server]
+fn test_unique_feeder() {
+    let users_file = common::temp_file("feeder-users.csv");
+    std::fs::write(
+        &users_file,
+        "username,password\nalice,one\nbob,two\ncarol,three\n",
+    )
+    .expect("failed to write feeder file");
+
+    let mock_alice = mock(GET, ALICE_PATH).return_status(200).create();
+    let mock_bob = mock(GET, BOB_PATH).return_status(200).create();
+    let mock_carol = mock(GET, CAROL_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_feeder(
+            GooseFeeder::csv("users", &users_file).set_strategy(GooseFeederStrategy::Unique),
+        )
+        .register_taskset(taskset!("LoadTest").register_task(task!(login).set_on_start()))
+        .execute();
+
+    let _ = std::fs::remove_file(&users_file);
+
+    let called_alice = mock_alice.times_called();
+    let called_bob = mock_bob.times_called();
+    let called_carol = mock_carol.times_called();
+
+    // Confirm each user logged in once, and no record was handed out twice.
+    assert_eq!(called_alice + called_bob + called_carol, 2);
+    assert!(called_alice <= 1);
+    assert!(called_bob <= 1);
+    assert!(called_carol <= 1);
+}
@@ -0,0 +1,84 @@
+use httpmock::Method::{GET, POST};
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::feeder::GooseFeeder;
+use goose::har::GooseHar;
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const LOGIN_PATH: &str = "/login";
+
+const HAR: &str = r#"{
+  "log": {
+    "version": "1.2",
+    "pages": [
+      {"id": "page_1", "title": "Home", "startedDateTime": "2020-07-14T09:41:00.000Z"},
+      {"id": "page_2", "title": "Login", "startedDateTime": "2020-07-14T09:41:00.500Z"}
+    ],
+    "entries": [
+      {
+        "pageref": "page_1",
+        "startedDateTime": "2020-07-14T09:41:00.000Z",
+        "time": 100,
+        "request": {"method": "GET", "url": "https://www.example.com/", "headers": []}
+      },
+      {
+        "pageref": "page_1",
+        "startedDateTime": "2020-07-14T09:41:00.050Z",
+        "time": 100,
+        "request": {"method": "GET", "url": "https://analytics.example.net/collect", "headers": []}
+      },
+      {
+        "pageref": "page_2",
+        "startedDateTime": "2020-07-14T09:41:00.500Z",
+        "time": 100,
+        "request": {
+          "method": "POST",
+          "url": "https://www.example.com/login",
+          "headers": [],
+          "postData": {"mimeType": "application/x-www-form-urlencoded", "text": "user=alice"}
+        }
+      }
+    ]
+  }
+}"#;
+
+#[test]
+#[with_mock_server]
+fn test_har_task_set() {
+    let har_file = common::temp_file("har-login.har");
+    let users_file = common::temp_file("har-users.csv");
+    std::fs::write(&har_file, HAR).expect("failed to write har file");
+    std::fs::write(&users_file, "username\nbob\n").expect("failed to write feeder file");
+
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_login = mock(POST, LOGIN_PATH)
+        .expect_body("user=bob")
+        .return_status(200)
+        .create();
+
+    let task_set = GooseHar::new("Login", &har_file)
+        .drop_third_party()
+        .set_substitution("alice", "users", "username")
+        .load()
+        .expect("failed to load har file");
+
+    let mut config = common::build_configuration();
+    config.run_time = "".to_string();
+    config.iterations = 2;
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_feeder(GooseFeeder::csv("users", &users_file))
+        .register_taskset(task_set)
+        .execute();
+
+    let _ = std::fs::remove_file(&har_file);
+    let _ = std::fs::remove_file(&users_file);
+
+    // Confirm the user made the requests of each page, in the order of the pages, with
+    // the recorded value replaced by the feeder record.
+    assert_eq!(mock_index.times_called(), 2);
+    assert_eq!(mock_login.times_called(), 2);
+}
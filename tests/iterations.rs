@@ -0,0 +1,88 @@
+use httpmock::Method::GET;
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::prelude::*;
+
+const LOGIN_PATH: &str = "/login";
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about";
+const LOGOUT_PATH: &str = "/logout";
+
+pub async fn login(user: &GooseUser) {
+    let _response = user.get(LOGIN_PATH).await;
+}
+
+pub async fn get_index(user: &GooseUser) {
+    let _response = user.get(INDEX_PATH).await;
+}
+
+pub async fn get_about(user: &GooseUser) {
+    let _response = user.get(ABOUT_PATH).await;
+}
+
+pub async fn logout(user: &GooseUser) {
+    let _response = user.get(LOGOUT_PATH).await;
+}
+
+#[test]
+#[with_mock_server]
+fn test_iterations() {
+    let mock_login = mock(GET, LOGIN_PATH).return_status(200).create();
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_about = mock(GET, ABOUT_PATH).return_status(200).create();
+    let mock_logout = mock(GET, LOGOUT_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    // The load test stops once all users complete their iterations.
+    config.run_time = "".to_string();
+    config.iterations = 3;
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(login).set_on_start())
+                .register_task(task!(get_index).set_weight(2))
+                .register_task(task!(get_about))
+                .register_task(task!(logout).set_on_stop()),
+        )
+        .execute();
+
+    // Confirm each user ran all weighted tasks 3 times.
+    assert_eq!(mock_login.times_called(), 2);
+    assert_eq!(mock_index.times_called(), 12);
+    assert_eq!(mock_about.times_called(), 6);
+    assert_eq!(mock_logout.times_called(), 2);
+}
+
+#[test]
+#[with_mock_server]
+fn test_taskset_iterations() {
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_about = mock(GET, ABOUT_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.run_time = "".to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_taskset(
+            taskset!("Index")
+                .set_iterations(2)
+                .register_task(task!(get_index)),
+        )
+        .register_taskset(
+            taskset!("About")
+                .set_iterations(5)
+                .register_task(task!(get_about)),
+        )
+        .execute();
+
+    // Confirm each task set ran as many times as configured.
+    assert_eq!(mock_index.times_called(), 2);
+    assert_eq!(mock_about.times_called(), 5);
+}
@@ -0,0 +1,78 @@
+use httpmock::Method::{GET, POST};
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const LOGIN_PATH: &str = "/login";
+const ABOUT_PATH: &str = "/about";
+
+#[test]
+#[with_mock_server]
+fn test_replay_stats_log() {
+    let stats_log_file = common::temp_file("replay-stats.csv");
+    std::fs::write(
+        &stats_log_file,
+        r#"elapsed,method,name,url,final_url,redirected,response_time,status_code,success,update,user,request_bytes,response_bytes,response_decompressed_bytes,connection_reused_estimate,http_version,source_address,error_kind,error
+10,POST,"login","http://example.com/login","http://example.com/login",false,2,200,true,false,0,120,0,0,false,HTTP/1.1,,,""
+12,GET,"index","http://example.com/","http://example.com/",false,2,200,true,false,0,80,512,512,true,HTTP/1.1,,,""
+12,GET,"index","http://example.com/","http://example.com/",false,2,500,false,true,0,80,512,512,true,HTTP/1.1,,,"""unexpected"" status"
+15,GET,"about","http://example.com/about","http://example.com/about",false,2,200,true,false,1,80,512,512,false,HTTP/1.1,,,""
+"#,
+    )
+    .expect("failed to write stats log");
+
+    let mock_login = mock(POST, LOGIN_PATH).return_status(200).create();
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_about = mock(GET, ABOUT_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    // One user replays the requests of each user in the log.
+    config.users = None;
+    config.run_time = "".to_string();
+    config.replay = stats_log_file.to_string();
+    config.replay_speed = "asap".to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .execute();
+
+    let _ = std::fs::remove_file(&stats_log_file);
+
+    // Confirm each logged request was replayed once, ignoring updates.
+    assert_eq!(mock_login.times_called(), 1);
+    assert_eq!(mock_index.times_called(), 1);
+    assert_eq!(mock_about.times_called(), 1);
+}
+
+#[test]
+#[with_mock_server]
+fn test_replay_access_log() {
+    let access_log_file = common::temp_file("replay-access.log");
+    std::fs::write(
+        &access_log_file,
+        r#"10.0.0.1 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.1" 200 2326
+10.0.0.2 - - [10/Oct/2000:13:55:36 -0700] "GET / HTTP/1.1" 200 2326
+10.0.0.1 - - [10/Oct/2000:13:55:37 -0700] "GET /about HTTP/1.1" 200 512 "http://example.com/" "Mozilla/5.0"
+"#,
+    )
+    .expect("failed to write access log");
+
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_about = mock(GET, ABOUT_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.users = None;
+    config.run_time = "".to_string();
+    config.replay = access_log_file.to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .execute();
+
+    let _ = std::fs::remove_file(&access_log_file);
+
+    // Confirm requests were replayed with the original timing.
+    assert_eq!(mock_index.times_called(), 2);
+    assert_eq!(mock_about.times_called(), 1);
+}
@@ -0,0 +1,58 @@
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+mod common;
+
+use goose::prelude::*;
+
+lazy_static! {
+    static ref TASKS_RUN: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+}
+
+pub async fn task_a(_user: &GooseUser) {
+    TASKS_RUN.lock().unwrap().push("a");
+}
+
+pub async fn task_b(_user: &GooseUser) {
+    TASKS_RUN.lock().unwrap().push("b");
+}
+
+pub async fn task_c(_user: &GooseUser) {
+    TASKS_RUN.lock().unwrap().push("c");
+}
+
+/// Run the load test with the given seed, returning the order tasks ran in.
+fn run_load_test(seed: u64) -> Vec<&'static str> {
+    TASKS_RUN.lock().unwrap().clear();
+
+    let mut config = common::build_configuration();
+    config.users = Some(1);
+    config.run_time = "".to_string();
+    config.iterations = 5;
+    config.seed = Some(seed);
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(task_a).set_weight(3))
+                .register_task(task!(task_b).set_weight(2))
+                .register_task(task!(task_c)),
+        )
+        .execute();
+
+    TASKS_RUN.lock().unwrap().clone()
+}
+
+#[test]
+fn test_seed() {
+    let first = run_load_test(7);
+    assert_eq!(first.len(), 30);
+
+    // Confirm the same seed runs tasks in the same order.
+    let second = run_load_test(7);
+    assert_eq!(first, second);
+
+    // Confirm another seed runs tasks in another order.
+    let other = run_load_test(8);
+    assert_ne!(first, other);
+}
@@ -0,0 +1,51 @@
+use httpmock::Method::GET;
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::prelude::*;
+
+const INDEX_PATH: &str = "/";
+const API_PATH: &str = "/api";
+const SLOW_PATH: &str = "/slow";
+
+pub async fn get_index(user: &GooseUser) {
+    let _response = user.get(INDEX_PATH).await;
+}
+
+pub async fn get_api(user: &GooseUser) {
+    let _response = user.get(API_PATH).await;
+}
+
+pub async fn get_slow(user: &GooseUser) {
+    let _response = user.get(SLOW_PATH).await;
+}
+
+#[test]
+#[with_mock_server]
+fn test_tags() {
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_api = mock(GET, API_PATH).return_status(200).create();
+    let mock_slow = mock(GET, SLOW_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.users = Some(2);
+    config.hatch_rate = 2;
+    config.tags = "api".to_string();
+    config.exclude_tags = "slow".to_string();
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_taskset(taskset!("Web").register_task(task!(get_index)))
+        .register_taskset(
+            taskset!("Api")
+                .set_tag("api")
+                .register_task(task!(get_api))
+                .register_task(task!(get_slow).set_tag("slow")),
+        )
+        .execute();
+
+    // Confirm only the selected task ran.
+    assert_ne!(mock_api.times_called(), 0);
+    assert_eq!(mock_index.times_called(), 0);
+    assert_eq!(mock_slow.times_called(), 0);
+}
@@ -0,0 +1,55 @@
+use httpmock::Method::GET;
+use httpmock::{mock, with_mock_server};
+
+mod common;
+
+use goose::prelude::*;
+
+const LOGIN_PATH: &str = "/login";
+const INDEX_PATH: &str = "/";
+const ABOUT_PATH: &str = "/about";
+const LOGOUT_PATH: &str = "/logout";
+
+pub async fn login(user: &GooseUser) {
+    let _response = user.get(LOGIN_PATH).await;
+}
+
+pub async fn get_index(user: &GooseUser) {
+    let _response = user.get(INDEX_PATH).await;
+}
+
+pub async fn get_about(user: &GooseUser) {
+    let _response = user.get(ABOUT_PATH).await;
+}
+
+pub async fn logout(user: &GooseUser) {
+    let _response = user.get(LOGOUT_PATH).await;
+}
+
+#[test]
+#[with_mock_server]
+fn test_validate() {
+    let mock_login = mock(GET, LOGIN_PATH).return_status(200).create();
+    let mock_index = mock(GET, INDEX_PATH).return_status(200).create();
+    let mock_about = mock(GET, ABOUT_PATH).return_status(200).create();
+    let mock_logout = mock(GET, LOGOUT_PATH).return_status(200).create();
+
+    let mut config = common::build_configuration();
+    config.validate = true;
+    crate::GooseAttack::initialize_with_config(config)
+        .setup()
+        .register_taskset(
+            taskset!("LoadTest")
+                .register_task(task!(login).set_on_start())
+                .register_task(task!(get_index).set_weight(9))
+                .register_task(task!(get_about).set_weight(3))
+                .register_task(task!(logout).set_on_stop()),
+        )
+        .execute();
+
+    // Confirm each task ran exactly once.
+    assert_eq!(mock_login.times_called(), 1);
+    assert_eq!(mock_index.times_called(), 1);
+    assert_eq!(mock_about.times_called(), 1);
+    assert_eq!(mock_logout.times_called(), 1);
+}